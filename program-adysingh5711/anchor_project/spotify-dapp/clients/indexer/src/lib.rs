@@ -0,0 +1,10 @@
+// Off-chain indexer for the Spotify dApp.
+// Builds "what the people I follow did" timelines from program accounts.
+
+pub mod source;
+pub mod timeline;
+
+mod tests;
+
+pub use source::*;
+pub use timeline::*;
@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AccountSerialize, Discriminator};
+
+/// Anything that can list the raw accounts owned by the Spotify program,
+/// e.g. a `getProgramAccounts` call against a local validator or a saved snapshot.
+pub trait AccountSource {
+    type Error;
+
+    fn program_accounts(&self) -> Result<Vec<(Pubkey, Vec<u8>)>, Self::Error>;
+
+    /// Accounts whose data matches every filter. RPC-backed sources should
+    /// override this to pass the filters to `getProgramAccounts`.
+    fn program_accounts_matching(
+        &self,
+        filters: &[Memcmp],
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>, Self::Error> {
+        Ok(self
+            .program_accounts()?
+            .into_iter()
+            .filter(|(_, data)| filters.iter().all(|filter| filter.matches(data)))
+            .collect())
+    }
+}
+
+/// Byte comparison against account data, as in a `getProgramAccounts` memcmp filter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Memcmp {
+    pub offset: usize,
+    pub bytes: Vec<u8>,
+}
+
+impl Memcmp {
+    /// Matches accounts of type `T` by their 8-byte discriminator.
+    pub fn discriminator<T: Discriminator>() -> Self {
+        Self {
+            offset: 0,
+            bytes: T::DISCRIMINATOR.to_vec(),
+        }
+    }
+
+    pub fn matches(&self, data: &[u8]) -> bool {
+        data.get(self.offset..self.offset + self.bytes.len()) == Some(&self.bytes[..])
+    }
+}
+
+/// In-memory set of program accounts keyed by address.
+#[derive(Default, Clone)]
+pub struct Snapshot {
+    accounts: BTreeMap<Pubkey, Vec<u8>>,
+}
+
+impl Snapshot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores raw account data, including the 8-byte discriminator.
+    pub fn insert_raw(&mut self, address: Pubkey, data: Vec<u8>) {
+        self.accounts.insert(address, data);
+    }
+
+    /// Serializes an Anchor account the same way the program would store it.
+    pub fn insert<T: AccountSerialize>(&mut self, address: Pubkey, account: &T) {
+        let mut data = Vec::new();
+        account
+            .try_serialize(&mut data)
+            .expect("serializing into a Vec cannot fail");
+        self.insert_raw(address, data);
+    }
+
+    pub fn remove(&mut self, address: &Pubkey) {
+        self.accounts.remove(address);
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+}
+
+impl AccountSource for Snapshot {
+    type Error = Infallible;
+
+    fn program_accounts(&self) -> Result<Vec<(Pubkey, Vec<u8>)>, Self::Error> {
+        Ok(self
+            .accounts
+            .iter()
+            .map(|(address, data)| (*address, data.clone()))
+            .collect())
+    }
+}
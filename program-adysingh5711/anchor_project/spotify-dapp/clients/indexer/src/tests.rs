@@ -0,0 +1,233 @@
+#[cfg(test)]
+mod timeline_tests {
+    use crate::*;
+    use anchor_lang::prelude::Pubkey;
+    use spotify_dapp::{ActivityFeed, UserFollow, UserProfile};
+
+    struct User {
+        wallet: Pubkey,
+        profile: Pubkey,
+    }
+
+    fn add_user(snapshot: &mut Snapshot, username: &str, created_at: i64) -> User {
+        let wallet = Pubkey::new_unique();
+        let (profile, _) = Pubkey::find_program_address(
+            &[b"user_profile", wallet.as_ref()],
+            &spotify_dapp::ID,
+        );
+        snapshot.insert(
+            profile,
+            &UserProfile {
+                authority: wallet,
+                username: username.to_string(),
+                display_name: String::new(),
+                bio: String::new(),
+                profile_image: String::new(),
+                followers_count: 0,
+                following_count: 0,
+                created_at,
+                search_entries_count: 0,
+            },
+        );
+        User { wallet, profile }
+    }
+
+    fn follow(snapshot: &mut Snapshot, follower: &User, following: &User, created_at: i64) {
+        let (address, _) = Pubkey::find_program_address(
+            &[b"user_follow", follower.wallet.as_ref(), following.profile.as_ref()],
+            &spotify_dapp::ID,
+        );
+        snapshot.insert(
+            address,
+            &UserFollow {
+                follower: follower.profile,
+                following: following.profile,
+                created_at,
+            },
+        );
+    }
+
+    fn add_feed(snapshot: &mut Snapshot, user: &User, activities: &[(&str, i64)]) {
+        let mut feed = ActivityFeed {
+            user: Pubkey::default(),
+            head: 0,
+            entries: Vec::new(),
+        };
+        for (metadata, created_at) in activities {
+            feed.record(
+                user.wallet,
+                ActivityFeed::ACTIVITY_TRACK_LIKED,
+                Pubkey::new_unique(),
+                metadata.to_string(),
+                *created_at,
+            );
+        }
+        let (address, _) = Pubkey::find_program_address(
+            &[b"activity_feed", user.wallet.as_ref()],
+            &spotify_dapp::ID,
+        );
+        snapshot.insert(address, &feed);
+    }
+
+    #[test]
+    fn merges_followed_feeds_newest_first() {
+        let mut snapshot = Snapshot::new();
+        let alice = add_user(&mut snapshot, "alice", 1);
+        let bob = add_user(&mut snapshot, "bob", 1);
+        let carol = add_user(&mut snapshot, "carol", 1);
+        let dave = add_user(&mut snapshot, "dave", 1);
+
+        follow(&mut snapshot, &alice, &bob, 1);
+        follow(&mut snapshot, &alice, &carol, 1);
+        add_feed(&mut snapshot, &bob, &[("bob 1", 10), ("bob 2", 30)]);
+        add_feed(&mut snapshot, &carol, &[("carol 1", 20), ("carol 2", 40)]);
+        add_feed(&mut snapshot, &dave, &[("dave 1", 50)]);
+
+        let indexer = TimelineIndexer::load(&snapshot).unwrap();
+        let timeline = indexer.timeline(&alice.wallet, 10);
+
+        let metadata: Vec<&str> = timeline.iter().map(|e| e.metadata.as_str()).collect();
+        assert_eq!(metadata, vec!["carol 2", "bob 2", "carol 1", "bob 1"]);
+        assert_eq!(timeline[0].username, "carol");
+        assert_eq!(timeline[0].actor, carol.wallet);
+        assert_eq!(timeline[0].actor_profile, carol.profile);
+    }
+
+    #[test]
+    fn respects_limit() {
+        let mut snapshot = Snapshot::new();
+        let alice = add_user(&mut snapshot, "alice", 1);
+        let bob = add_user(&mut snapshot, "bob", 1);
+
+        follow(&mut snapshot, &alice, &bob, 1);
+        add_feed(&mut snapshot, &bob, &[("one", 1), ("two", 2), ("three", 3)]);
+
+        let indexer = TimelineIndexer::load(&snapshot).unwrap();
+        let timeline = indexer.timeline(&alice.wallet, 2);
+
+        assert_eq!(timeline.len(), 2);
+        assert_eq!(timeline[0].metadata, "three");
+        assert_eq!(timeline[1].metadata, "two");
+    }
+
+    #[test]
+    fn orders_wrapped_ring_buffer() {
+        let mut snapshot = Snapshot::new();
+        let bob = add_user(&mut snapshot, "bob", 1);
+
+        let total = ActivityFeed::CAPACITY + 5;
+        let labels: Vec<String> = (0..total).map(|i| format!("entry {}", i)).collect();
+        let activities: Vec<(&str, i64)> = labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| (label.as_str(), i as i64))
+            .collect();
+        add_feed(&mut snapshot, &bob, &activities);
+
+        let indexer = TimelineIndexer::load(&snapshot).unwrap();
+        let entries = indexer.feed_entries(&bob.wallet);
+
+        assert_eq!(entries.len(), ActivityFeed::CAPACITY);
+        assert_eq!(entries[0].metadata, "entry 5");
+        assert_eq!(entries[0].sequence, 5);
+        assert_eq!(entries.last().unwrap().metadata, format!("entry {}", total - 1));
+    }
+
+    #[test]
+    fn skips_follows_of_closed_profiles() {
+        let mut snapshot = Snapshot::new();
+        let alice = add_user(&mut snapshot, "alice", 1);
+        let bob = add_user(&mut snapshot, "bob", 1);
+
+        follow(&mut snapshot, &alice, &bob, 1);
+        add_feed(&mut snapshot, &bob, &[("bob 1", 10)]);
+        snapshot.remove(&bob.profile);
+
+        let indexer = TimelineIndexer::load(&snapshot).unwrap();
+
+        assert!(indexer.following(&alice.wallet).is_empty());
+        assert!(indexer.timeline(&alice.wallet, 10).is_empty());
+    }
+
+    #[test]
+    fn skips_follows_of_recreated_profiles() {
+        let mut snapshot = Snapshot::new();
+        let alice = add_user(&mut snapshot, "alice", 1);
+        let bob = add_user(&mut snapshot, "bob", 1);
+        let carol = add_user(&mut snapshot, "carol", 1);
+
+        follow(&mut snapshot, &alice, &bob, 5);
+        follow(&mut snapshot, &alice, &carol, 5);
+        add_feed(&mut snapshot, &bob, &[("bob 1", 10)]);
+        add_feed(&mut snapshot, &carol, &[("carol 1", 20)]);
+
+        // Bob's profile is closed and re-created after Alice followed it
+        snapshot.remove(&bob.profile);
+        snapshot.insert(
+            bob.profile,
+            &UserProfile {
+                authority: bob.wallet,
+                username: "bob".to_string(),
+                display_name: String::new(),
+                bio: String::new(),
+                profile_image: String::new(),
+                followers_count: 0,
+                following_count: 0,
+                created_at: 8,
+                search_entries_count: 0,
+            },
+        );
+
+        let indexer = TimelineIndexer::load(&snapshot).unwrap();
+
+        assert_eq!(indexer.following(&alice.wallet), vec![carol.wallet]);
+        let timeline = indexer.timeline(&alice.wallet, 10);
+        assert_eq!(timeline.len(), 1);
+        assert_eq!(timeline[0].metadata, "carol 1");
+    }
+
+    #[test]
+    fn ignores_unrelated_accounts() {
+        let mut snapshot = Snapshot::new();
+        let alice = add_user(&mut snapshot, "alice", 1);
+        snapshot.insert_raw(Pubkey::new_unique(), vec![1, 2, 3]);
+
+        let indexer = TimelineIndexer::load(&snapshot).unwrap();
+
+        assert!(indexer.timeline(&alice.wallet, 10).is_empty());
+        assert!(indexer.timeline(&Pubkey::new_unique(), 10).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod source_tests {
+    use crate::*;
+    use anchor_lang::prelude::Pubkey;
+    use spotify_dapp::{UserFollow, UserProfile};
+
+    #[test]
+    fn filters_by_discriminator() {
+        let mut snapshot = Snapshot::new();
+        let follow = Pubkey::new_unique();
+        snapshot.insert(
+            follow,
+            &UserFollow {
+                follower: Pubkey::new_unique(),
+                following: Pubkey::new_unique(),
+                created_at: 0,
+            },
+        );
+        snapshot.insert_raw(Pubkey::new_unique(), vec![1, 2, 3]);
+
+        let follows = snapshot
+            .program_accounts_matching(&[Memcmp::discriminator::<UserFollow>()])
+            .unwrap();
+        let profiles = snapshot
+            .program_accounts_matching(&[Memcmp::discriminator::<UserProfile>()])
+            .unwrap();
+
+        assert_eq!(follows.len(), 1);
+        assert_eq!(follows[0].0, follow);
+        assert!(profiles.is_empty());
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountDeserialize;
+use spotify_dapp::{ActivityEntry, ActivityFeed, UserFollow, UserProfile};
+
+use crate::source::AccountSource;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineEntry {
+    pub actor: Pubkey,            // Wallet of the user who performed the activity
+    pub actor_profile: Pubkey,    // UserProfile PDA of that user
+    pub username: String,         // Username at indexing time
+    pub sequence: u64,            // Position in the actor's own feed
+    pub activity_type: u8,
+    pub target: Pubkey,
+    pub metadata: String,
+    pub created_at: i64,
+}
+
+/// Index of profiles, follow relationships and activity feeds used to build
+/// follower timelines. Accounts that are not one of those types are ignored.
+#[derive(Default)]
+pub struct TimelineIndexer {
+    profiles: HashMap<Pubkey, UserProfile>,          // Profile PDA -> profile
+    profile_by_wallet: HashMap<Pubkey, Pubkey>,      // Wallet -> profile PDA
+    following: HashMap<Pubkey, BTreeMap<Pubkey, i64>>, // Follower profile -> followed profile -> followed at
+    feeds: HashMap<Pubkey, ActivityFeed>,            // Wallet -> activity feed
+}
+
+impl TimelineIndexer {
+    pub fn load<S: AccountSource>(source: &S) -> Result<Self, S::Error> {
+        let mut indexer = Self::default();
+        for (address, data) in source.program_accounts()? {
+            indexer.ingest(address, &data);
+        }
+        Ok(indexer)
+    }
+
+    /// Adds a single raw account to the index.
+    pub fn ingest(&mut self, address: Pubkey, data: &[u8]) {
+        if let Ok(profile) = UserProfile::try_deserialize(&mut &data[..]) {
+            self.profile_by_wallet.insert(profile.authority, address);
+            self.profiles.insert(address, profile);
+        } else if let Ok(follow) = UserFollow::try_deserialize(&mut &data[..]) {
+            self.following
+                .entry(follow.follower)
+                .or_default()
+                .insert(follow.following, follow.created_at);
+        } else if let Ok(feed) = ActivityFeed::try_deserialize(&mut &data[..]) {
+            self.feeds.insert(feed.user, feed);
+        }
+    }
+
+    /// Wallets followed by `wallet`. Follows pointing at closed profiles are skipped, as are
+    /// follows older than the profile they point at, since that profile was closed and re-created.
+    pub fn following(&self, wallet: &Pubkey) -> Vec<Pubkey> {
+        let Some(profile) = self.profile_by_wallet.get(wallet) else {
+            return Vec::new();
+        };
+
+        self.following
+            .get(profile)
+            .into_iter()
+            .flatten()
+            .filter_map(|(followed, followed_at)| {
+                self.profiles
+                    .get(followed)
+                    .filter(|profile| profile.created_at <= *followed_at)
+            })
+            .map(|followed| followed.authority)
+            .collect()
+    }
+
+    /// Merged activity of everyone `wallet` follows, newest first.
+    pub fn timeline(&self, wallet: &Pubkey, limit: usize) -> Vec<TimelineEntry> {
+        let mut entries: Vec<TimelineEntry> = self
+            .following(wallet)
+            .into_iter()
+            .flat_map(|actor| self.feed_entries(&actor))
+            .collect();
+
+        entries.sort_by(|a, b| {
+            b.created_at
+                .cmp(&a.created_at)
+                .then(b.sequence.cmp(&a.sequence))
+                .then(a.actor.cmp(&b.actor))
+        });
+        entries.truncate(limit);
+        entries
+    }
+
+    /// Activity of a single wallet in the order it was recorded.
+    pub fn feed_entries(&self, wallet: &Pubkey) -> Vec<TimelineEntry> {
+        let (Some(feed), Some(profile_key)) =
+            (self.feeds.get(wallet), self.profile_by_wallet.get(wallet))
+        else {
+            return Vec::new();
+        };
+        let profile = &self.profiles[profile_key];
+
+        ordered_entries(feed)
+            .map(|(sequence, entry)| TimelineEntry {
+                actor: *wallet,
+                actor_profile: *profile_key,
+                username: profile.username.clone(),
+                sequence,
+                activity_type: entry.activity_type,
+                target: entry.target,
+                metadata: entry.metadata.clone(),
+                created_at: entry.created_at,
+            })
+            .collect()
+    }
+}
+
+/// Walks the ring buffer from the oldest surviving entry to the newest one.
+fn ordered_entries(feed: &ActivityFeed) -> impl Iterator<Item = (u64, &ActivityEntry)> {
+    let len = feed.entries.len() as u64;
+    let oldest = feed.head.saturating_sub(len);
+
+    (oldest..feed.head).filter_map(move |sequence| {
+        let slot = (sequence % ActivityFeed::CAPACITY as u64) as usize;
+        feed.entries.get(slot).map(|entry| (sequence, entry))
+    })
+}
@@ -0,0 +1,165 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountDeserialize;
+use spotify_dapp::instructions::search::{SearchFilters, SearchResult};
+use spotify_dapp::{Playlist, Track, UserProfile};
+use spotify_indexer::{AccountSource, Memcmp};
+
+// Points per query word
+const EXACT_WORD_SCORE: u32 = 2;
+const PREFIX_SCORE: u32 = 1;
+
+/// Searchable copy of every track, public playlist and user profile.
+#[derive(Default)]
+pub struct SearchEngine {
+    tracks: Vec<(Pubkey, Track)>,
+    playlists: Vec<(Pubkey, Playlist)>,
+    users: Vec<(Pubkey, UserProfile)>,
+}
+
+impl SearchEngine {
+    /// Fetches each account type with a discriminator memcmp filter.
+    pub fn load<S: AccountSource>(source: &S) -> Result<Self, S::Error> {
+        let mut engine = Self::default();
+
+        for (address, data) in source.program_accounts_matching(&[Memcmp::discriminator::<Track>()])? {
+            engine.ingest(address, &data);
+        }
+        for (address, data) in source.program_accounts_matching(&[Memcmp::discriminator::<Playlist>()])? {
+            engine.ingest(address, &data);
+        }
+        for (address, data) in source.program_accounts_matching(&[Memcmp::discriminator::<UserProfile>()])? {
+            engine.ingest(address, &data);
+        }
+
+        Ok(engine)
+    }
+
+    /// Adds a single raw account. Private playlists and unrelated accounts are ignored.
+    pub fn ingest(&mut self, address: Pubkey, data: &[u8]) {
+        if let Ok(track) = Track::try_deserialize(&mut &data[..]) {
+            self.tracks.push((address, track));
+        } else if let Ok(playlist) = Playlist::try_deserialize(&mut &data[..]) {
+            if playlist.is_public {
+                self.playlists.push((address, playlist));
+            }
+        } else if let Ok(profile) = UserProfile::try_deserialize(&mut &data[..]) {
+            self.users.push((address, profile));
+        }
+    }
+
+    /// Results matching every word of `query` and all of `filters`, best first.
+    ///
+    /// Each query word must be a prefix of a word in the result's title or
+    /// subtitle; whole-word matches rank above prefix matches, then likes and
+    /// recency break ties. An empty query matches everything. Track-only filters
+    /// (genre, duration) exclude playlists and users.
+    pub fn search(&self, query: &str, filters: &SearchFilters, limit: usize) -> Vec<SearchResult> {
+        let query_words = words(query);
+        let track_only = filters.genre.is_some()
+            || filters.min_duration.is_some()
+            || filters.max_duration.is_some();
+
+        let tracks = self
+            .tracks
+            .iter()
+            .filter(|(_, track)| track_matches(track, filters))
+            .map(|(address, track)| SearchResult {
+                result_type: SearchResult::RESULT_TYPE_TRACK,
+                pubkey: *address,
+                title: track.title.clone(),
+                subtitle: track.artist.clone(),
+                likes_count: track.likes_count,
+                created_at: track.created_at,
+            });
+
+        let playlists = self
+            .playlists
+            .iter()
+            .filter(|_| !track_only)
+            .map(|(address, playlist)| SearchResult {
+                result_type: SearchResult::RESULT_TYPE_PLAYLIST,
+                pubkey: *address,
+                title: playlist.name.clone(),
+                subtitle: playlist.description.clone(),
+                likes_count: playlist.likes_count,
+                created_at: playlist.created_at,
+            });
+
+        // Followers stand in for likes on user results
+        let users = self
+            .users
+            .iter()
+            .filter(|_| !track_only)
+            .map(|(address, profile)| SearchResult {
+                result_type: SearchResult::RESULT_TYPE_USER,
+                pubkey: *address,
+                title: profile.username.clone(),
+                subtitle: profile.display_name.clone(),
+                likes_count: profile.followers_count,
+                created_at: profile.created_at,
+            });
+
+        let mut ranked: Vec<(u32, SearchResult)> = tracks
+            .chain(playlists)
+            .chain(users)
+            .filter(|result| common_matches(result, filters))
+            .filter_map(|result| {
+                score(&query_words, &result).map(|score| (score, result))
+            })
+            .collect();
+
+        ranked.sort_by(|(score_a, a), (score_b, b)| {
+            score_b
+                .cmp(score_a)
+                .then(b.likes_count.cmp(&a.likes_count))
+                .then(b.created_at.cmp(&a.created_at))
+                .then(a.pubkey.cmp(&b.pubkey))
+        });
+        ranked.truncate(limit);
+        ranked.into_iter().map(|(_, result)| result).collect()
+    }
+}
+
+fn track_matches(track: &Track, filters: &SearchFilters) -> bool {
+    filters
+        .genre
+        .as_ref()
+        .is_none_or(|genre| track.genre.eq_ignore_ascii_case(genre))
+        && filters.min_duration.is_none_or(|min| track.duration >= min)
+        && filters.max_duration.is_none_or(|max| track.duration <= max)
+}
+
+fn common_matches(result: &SearchResult, filters: &SearchFilters) -> bool {
+    filters.min_likes.is_none_or(|min| result.likes_count >= min)
+        && filters.created_after.is_none_or(|after| result.created_at > after)
+}
+
+/// `None` when some query word matches nothing in the result.
+fn score(query_words: &[String], result: &SearchResult) -> Option<u32> {
+    let mut text_words = words(&result.title);
+    text_words.extend(words(&result.subtitle));
+
+    query_words.iter().try_fold(0, |total, query_word| {
+        let best = text_words
+            .iter()
+            .filter_map(|word| {
+                if word == query_word {
+                    Some(EXACT_WORD_SCORE)
+                } else if word.starts_with(query_word.as_str()) {
+                    Some(PREFIX_SCORE)
+                } else {
+                    None
+                }
+            })
+            .max()?;
+        Some(total + best)
+    })
+}
+
+/// Lowercased words, split the same way as the on-chain `SearchIndex::tokenize`.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
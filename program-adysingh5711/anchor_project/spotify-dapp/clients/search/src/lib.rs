@@ -0,0 +1,10 @@
+// Off-chain search for the Spotify dApp.
+// Applies `SearchFilters` to fetched accounts and returns ranked `SearchResult`s.
+
+pub mod engine;
+
+mod tests;
+
+pub use engine::*;
+pub use spotify_dapp::instructions::search::{SearchFilters, SearchResult};
+pub use spotify_indexer::{AccountSource, Memcmp, Snapshot};
@@ -0,0 +1,182 @@
+#[cfg(test)]
+mod search_tests {
+    use crate::*;
+    use anchor_lang::prelude::Pubkey;
+    use spotify_dapp::{Playlist, Track, UserProfile};
+
+    fn add_track(
+        snapshot: &mut Snapshot,
+        title: &str,
+        artist: &str,
+        genre: &str,
+        duration: u64,
+        likes_count: u64,
+        created_at: i64,
+    ) -> Pubkey {
+        let (address, _) = Pubkey::find_program_address(
+            &[b"track", title.as_bytes(), artist.as_bytes()],
+            &spotify_dapp::ID,
+        );
+        snapshot.insert(
+            address,
+            &Track {
+                title: title.to_string(),
+                artist: artist.to_string(),
+                album: String::new(),
+                genre: genre.to_string(),
+                duration,
+                audio_url: String::new(),
+                cover_image: String::new(),
+                likes_count,
+                plays_count: 0,
+                created_by: Pubkey::new_unique(),
+                created_at,
+                tips_received: 0,
+                price_lamports: 0,
+                is_premium: false,
+                album_key: None,
+                artist_profile: None,
+                playlists_count: 0,
+                search_entries_count: 0,
+                listeners_count: 0,
+            },
+        );
+        address
+    }
+
+    fn add_playlist(snapshot: &mut Snapshot, name: &str, is_public: bool, likes_count: u64) -> Pubkey {
+        let address = Pubkey::new_unique();
+        snapshot.insert(
+            address,
+            &Playlist {
+                authority: Pubkey::new_unique(),
+                name: name.to_string(),
+                description: String::new(),
+                is_public,
+                tracks_count: 0,
+                likes_count,
+                plays_count: 0,
+                is_collaborative: false,
+                created_at: 1,
+                updated_at: 1,
+                collaborators_count: 0,
+                search_entries_count: 0,
+            },
+        );
+        address
+    }
+
+    fn add_user(snapshot: &mut Snapshot, username: &str, display_name: &str) -> Pubkey {
+        let address = Pubkey::new_unique();
+        snapshot.insert(
+            address,
+            &UserProfile {
+                authority: Pubkey::new_unique(),
+                username: username.to_string(),
+                display_name: display_name.to_string(),
+                bio: String::new(),
+                profile_image: String::new(),
+                followers_count: 0,
+                following_count: 0,
+                created_at: 1,
+                search_entries_count: 0,
+            },
+        );
+        address
+    }
+
+    fn keys(results: &[SearchResult]) -> Vec<Pubkey> {
+        results.iter().map(|result| result.pubkey).collect()
+    }
+
+    #[test]
+    fn matches_prefixes_across_account_types() {
+        let mut snapshot = Snapshot::new();
+        let track = add_track(&mut snapshot, "Midnight Drive", "Nova", "synthwave", 200, 0, 1);
+        let playlist = add_playlist(&mut snapshot, "Midnight Mix", true, 0);
+        let user = add_user(&mut snapshot, "midas", "Mid Tempo");
+        add_track(&mut snapshot, "Sunrise", "Nova", "pop", 180, 0, 1);
+
+        let engine = SearchEngine::load(&snapshot).unwrap();
+        let results = engine.search("mid", &SearchFilters::default(), 10);
+
+        assert_eq!(results.len(), 3);
+        for key in [track, playlist, user] {
+            assert!(keys(&results).contains(&key));
+        }
+    }
+
+    #[test]
+    fn requires_every_query_word() {
+        let mut snapshot = Snapshot::new();
+        let track = add_track(&mut snapshot, "Midnight Drive", "Nova", "synthwave", 200, 0, 1);
+        add_track(&mut snapshot, "Midnight Walk", "Luna", "ambient", 200, 0, 1);
+
+        let engine = SearchEngine::load(&snapshot).unwrap();
+        let results = engine.search("midnight nov", &SearchFilters::default(), 10);
+
+        assert_eq!(keys(&results), vec![track]);
+        assert_eq!(results[0].subtitle, "Nova");
+        assert_eq!(results[0].result_type, SearchResult::RESULT_TYPE_TRACK);
+    }
+
+    #[test]
+    fn ranks_exact_words_then_likes() {
+        let mut snapshot = Snapshot::new();
+        let prefix = add_track(&mut snapshot, "Rockets", "Band", "rock", 200, 50, 1);
+        let exact_popular = add_track(&mut snapshot, "Rock Anthem", "Band", "rock", 200, 10, 1);
+        let exact_new = add_track(&mut snapshot, "Rock Ballad", "Band", "rock", 200, 0, 5);
+        let exact_old = add_track(&mut snapshot, "Rock Opera", "Band", "rock", 200, 0, 2);
+
+        let engine = SearchEngine::load(&snapshot).unwrap();
+        let results = engine.search("rock", &SearchFilters::default(), 10);
+
+        assert_eq!(keys(&results), vec![exact_popular, exact_new, exact_old, prefix]);
+    }
+
+    #[test]
+    fn applies_track_filters() {
+        let mut snapshot = Snapshot::new();
+        let short = add_track(&mut snapshot, "Short", "A", "Jazz", 90, 5, 10);
+        let long = add_track(&mut snapshot, "Long", "A", "jazz", 600, 5, 10);
+        add_track(&mut snapshot, "Other", "A", "pop", 90, 5, 10);
+        add_track(&mut snapshot, "Unliked", "A", "jazz", 90, 0, 10);
+        add_track(&mut snapshot, "Old", "A", "jazz", 90, 5, 1);
+        add_playlist(&mut snapshot, "Jazz Mix", true, 5);
+
+        let engine = SearchEngine::load(&snapshot).unwrap();
+        let filters = SearchFilters {
+            genre: Some("jazz".to_string()),
+            min_likes: Some(1),
+            created_after: Some(5),
+            ..Default::default()
+        };
+
+        let mut results = keys(&engine.search("", &filters, 10));
+        results.sort();
+        let mut expected = vec![short, long];
+        expected.sort();
+        assert_eq!(results, expected);
+
+        let filters = SearchFilters {
+            max_duration: Some(120),
+            ..filters
+        };
+        assert_eq!(keys(&engine.search("", &filters, 10)), vec![short]);
+    }
+
+    #[test]
+    fn skips_private_playlists_and_respects_limit() {
+        let mut snapshot = Snapshot::new();
+        add_playlist(&mut snapshot, "Secret Chill", false, 100);
+        let top = add_playlist(&mut snapshot, "Chill Beats", true, 20);
+        add_playlist(&mut snapshot, "Chill Vibes", true, 10);
+        snapshot.insert_raw(Pubkey::new_unique(), vec![1, 2, 3]);
+
+        let engine = SearchEngine::load(&snapshot).unwrap();
+        let results = engine.search("chill", &SearchFilters::default(), 1);
+
+        assert_eq!(keys(&results), vec![top]);
+        assert_eq!(results[0].result_type, SearchResult::RESULT_TYPE_PLAYLIST);
+    }
+}
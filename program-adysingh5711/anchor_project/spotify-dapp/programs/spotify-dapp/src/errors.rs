@@ -73,7 +73,7 @@ pub enum SpotifyError {
     InvalidPermissions,
 
     // Search errors
-    #[msg("Search term cannot be longer than 64 characters")]
+    #[msg("Search term cannot be longer than 32 characters")]
     SearchTermTooLong,
     #[msg("Search term cannot be empty")]
     SearchTermEmpty,
@@ -91,4 +91,116 @@ pub enum SpotifyError {
     InvalidAccount,
     #[msg("Unauthorized action")]
     Unauthorized,
+
+    // Playlist ordering and deletion errors
+    #[msg("Position is outside the playlist")]
+    InvalidPlaylistPosition,
+    #[msg("Playlist track accounts do not match the positions being shifted")]
+    PlaylistTracksMismatch,
+    #[msg("Playlist still has tracks, likes or collaborators that were not passed in to be closed")]
+    PlaylistNotEmpty,
+    #[msg("Playlist is private")]
+    PlaylistNotVisible,
+
+    // Profile lifecycle errors
+    #[msg("Unfollow all users before closing the profile")]
+    ProfileStillFollowing,
+    #[msg("Delete all tracks and playlists before closing the profile")]
+    ProfileStillHasContent,
+    #[msg("Followed profile still exists")]
+    FollowNotOrphaned,
+    #[msg("Followed profile was closed or re-created, remove the orphaned follow instead")]
+    FollowOrphaned,
+    #[msg("Activity feed already uses the current layout")]
+    ActivityFeedAlreadyMigrated,
+
+    // Search index errors
+    #[msg("Invalid search target type")]
+    InvalidSearchTargetType,
+    #[msg("Search index accounts do not match the indexed terms")]
+    SearchIndexMismatch,
+    #[msg("Search term does not appear in the target's searchable text")]
+    SearchTermNotInTarget,
+
+    // Recommendation errors
+    #[msg("Invalid recommendation type")]
+    InvalidRecommendationType,
+    #[msg("Recommendation target does not match the recommendation type")]
+    InvalidRecommendationTarget,
+    #[msg("Recommendation has expired")]
+    RecommendationExpired,
+    #[msg("Recommendation has not expired yet")]
+    RecommendationNotExpired,
+
+    // Config errors
+    #[msg("Recommender is already registered")]
+    RecommenderAlreadyAdded,
+    #[msg("Recommender is not registered")]
+    RecommenderNotFound,
+    #[msg("Too many recommenders registered")]
+    TooManyRecommenders,
+    #[msg("Platform fee exceeds the maximum")]
+    InvalidPlatformFee,
+
+    // Play errors
+    #[msg("Played duration cannot exceed the track duration")]
+    PlayDurationExceedsTrack,
+    #[msg("Track was not played long enough to count")]
+    PlayTooShort,
+    #[msg("Track was played too recently to count again")]
+    PlayCooldownActive,
+
+    // Tip errors
+    #[msg("Tip amount must be greater than 0")]
+    InvalidTipAmount,
+    #[msg("Cannot tip your own track")]
+    CannotTipOwnTrack,
+
+    // Payment errors
+    #[msg("Paid tracks require the creator escrow, config and fee recipient accounts")]
+    PaymentAccountsMissing,
+    #[msg("Fee recipient does not match the program config")]
+    InvalidFeeRecipient,
+    #[msg("No earnings to withdraw")]
+    NoEarningsToWithdraw,
+    #[msg("Create the creator escrow before setting a track price")]
+    CreatorEscrowMissing,
+
+    // Subscription errors
+    #[msg("An active subscription is required for premium tracks")]
+    SubscriptionRequired,
+    #[msg("Subscription period must be between 1 and 12 months")]
+    InvalidSubscriptionPeriod,
+    #[msg("Subscriptions are not available yet")]
+    SubscriptionPriceNotSet,
+    #[msg("Treasury does not hold enough lamports above its rent")]
+    InsufficientTreasuryFunds,
+
+    // Artist errors
+    #[msg("Artist name cannot be empty")]
+    ArtistNameEmpty,
+    #[msg("Track artist must match the linked artist profile name")]
+    ArtistNameMismatch,
+    #[msg("Another verified artist already uses this name")]
+    ArtistNameTaken,
+    #[msg("Artist name belongs to a verified artist, link their artist profile to use it")]
+    ArtistNameClaimed,
+
+    // Album errors
+    #[msg("Album name cannot be empty")]
+    AlbumNameEmpty,
+    #[msg("Track already belongs to an album")]
+    TrackAlreadyInAlbum,
+    #[msg("Track belongs to an album and cannot be deleted")]
+    TrackInAlbum,
+    #[msg("Linking an album requires both the album and album track accounts")]
+    AlbumAccountsMissing,
+
+    // Track lifecycle errors
+    #[msg("Track is still in playlists, or its likes and plays were not passed in to be closed")]
+    TrackInUse,
+
+    // Username errors
+    #[msg("Username is already taken")]
+    UsernameTaken,
 }
@@ -0,0 +1,384 @@
+use anchor_lang::prelude::*;
+
+// User events
+#[event]
+pub struct UserProfileCreated {
+    pub profile: Pubkey,
+    pub authority: Pubkey,
+    pub username: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UserProfileUpdated {
+    pub profile: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UsernameChanged {
+    pub profile: Pubkey,
+    pub authority: Pubkey,
+    pub old_username: String,
+    pub new_username: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UserProfileClosed {
+    pub profile: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+// Track events
+#[event]
+pub struct TrackCreated {
+    pub track: Pubkey,
+    pub creator: Pubkey,
+    pub title: String,
+    pub artist: String,
+    pub artist_profile: Option<Pubkey>,
+    pub genre: String,
+    pub price_lamports: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TrackUpdated {
+    pub track: Pubkey,
+    pub creator: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TrackDeleted {
+    pub track: Pubkey,
+    pub creator: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TrackPlayed {
+    pub track: Pubkey,
+    pub user: Pubkey,
+    pub duration_played: u64,
+    pub play_count: u64,
+    pub amount_paid: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TrackTipped {
+    pub track: Pubkey,
+    pub tipper: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+    pub total_tips: u64,
+    pub timestamp: i64,
+}
+
+// Artist events
+#[event]
+pub struct ArtistProfileCreated {
+    pub artist_profile: Pubkey,
+    pub authority: Pubkey,
+    pub user_profile: Pubkey,
+    pub name: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ArtistVerificationUpdated {
+    pub artist_profile: Pubkey,
+    pub is_verified: bool,
+    pub timestamp: i64,
+}
+
+// Album events
+#[event]
+pub struct AlbumCreated {
+    pub album: Pubkey,
+    pub creator: Pubkey,
+    pub title: String,
+    pub released_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TrackAddedToAlbum {
+    pub album: Pubkey,
+    pub track: Pubkey,
+    pub position: u64,
+    pub timestamp: i64,
+}
+
+// Playlist events
+#[event]
+pub struct PlaylistCreated {
+    pub playlist: Pubkey,
+    pub authority: Pubkey,
+    pub name: String,
+    pub is_public: bool,
+    pub is_collaborative: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlaylistUpdated {
+    pub playlist: Pubkey,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlaylistDeleted {
+    pub playlist: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TrackAddedToPlaylist {
+    pub playlist: Pubkey,
+    pub track: Pubkey,
+    pub added_by: Pubkey,
+    pub position: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TrackRemovedFromPlaylist {
+    pub playlist: Pubkey,
+    pub track: Pubkey,
+    pub removed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlaylistTrackMoved {
+    pub playlist: Pubkey,
+    pub track: Pubkey,
+    pub moved_by: Pubkey,
+    pub old_position: u64,
+    pub new_position: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CollaboratorAdded {
+    pub playlist: Pubkey,
+    pub collaborator: Pubkey,
+    pub permissions: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CollaboratorPermissionsUpdated {
+    pub playlist: Pubkey,
+    pub collaborator: Pubkey,
+    pub permissions: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CollaboratorRemoved {
+    pub playlist: Pubkey,
+    pub collaborator: Pubkey,
+    pub timestamp: i64,
+}
+
+// Social events
+#[event]
+pub struct TrackLiked {
+    pub track: Pubkey,
+    pub user: Pubkey,
+    pub likes_count: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TrackUnliked {
+    pub track: Pubkey,
+    pub user: Pubkey,
+    pub likes_count: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlaylistLiked {
+    pub playlist: Pubkey,
+    pub user: Pubkey,
+    pub likes_count: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlaylistUnliked {
+    pub playlist: Pubkey,
+    pub user: Pubkey,
+    pub likes_count: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UserFollowed {
+    pub follower: Pubkey,
+    pub following: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UserUnfollowed {
+    pub follower: Pubkey,
+    pub following: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrphanedFollowRemoved {
+    pub follower: Pubkey,
+    pub following: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ActivityFeedMigrated {
+    pub user: Pubkey,
+    pub timestamp: i64,
+}
+
+// Search events
+#[event]
+pub struct SearchIndexCreated {
+    pub search_index: Pubkey,
+    pub search_term: String,
+    pub target_type: u8,
+    pub target_pubkey: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SearchIndexRemoved {
+    pub search_index: Pubkey,
+    pub search_term: String,
+    pub target_pubkey: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SearchIndexUpdated {
+    pub old_search_index: Pubkey,
+    pub new_search_index: Pubkey,
+    pub old_term: String,
+    pub new_term: String,
+    pub target_pubkey: Pubkey,
+    pub timestamp: i64,
+}
+
+// Analytics events
+#[event]
+pub struct UserInsightsGenerated {
+    pub user: Pubkey,
+    pub total_listening_time: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecommendationCreated {
+    pub recommendation: Pubkey,
+    pub user: Pubkey,
+    pub recommendation_type: u8,
+    pub target: Pubkey,
+    pub score: f32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecommendationViewed {
+    pub recommendation: Pubkey,
+    pub user: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecommendationDismissed {
+    pub recommendation: Pubkey,
+    pub user: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecommendationExpired {
+    pub recommendation: Pubkey,
+    pub user: Pubkey,
+    pub timestamp: i64,
+}
+
+// Config events
+#[event]
+pub struct ConfigInitialized {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecommenderAdded {
+    pub recommender: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecommenderRemoved {
+    pub recommender: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SubscriptionPriceUpdated {
+    pub subscription_price_lamports: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlatformFeeUpdated {
+    pub platform_fee_bps: u16,
+    pub fee_recipient: Pubkey,
+    pub timestamp: i64,
+}
+
+// Payment events
+#[event]
+pub struct CreatorEscrowCreated {
+    pub creator_escrow: Pubkey,
+    pub creator: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EarningsWithdrawn {
+    pub creator_escrow: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct Subscribed {
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub periods: u8,
+    pub amount: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TreasuryWithdrawn {
+    pub treasury: Pubkey,
+    pub admin: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
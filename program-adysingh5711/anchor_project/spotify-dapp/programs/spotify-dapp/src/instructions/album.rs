@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::SpotifyError;
+use crate::events::*;
+
+pub fn create_album(
+    ctx: Context<CreateAlbum>,
+    title: String,
+    cover_image: String,
+    released_at: Option<i64>,
+) -> Result<()> {
+    let album = &mut ctx.accounts.album;
+    let clock = Clock::get()?;
+
+    require!(title.len() <= 64, SpotifyError::AlbumNameTooLong);
+    require!(!title.is_empty(), SpotifyError::AlbumNameEmpty);
+    require!(cover_image.len() <= 256, SpotifyError::CoverImageUrlTooLong);
+
+    album.creator = ctx.accounts.authority.key();
+    album.title = title;
+    album.cover_image = cover_image;
+    album.released_at = released_at.unwrap_or(clock.unix_timestamp);
+    album.track_count = 0;
+    album.created_at = clock.unix_timestamp;
+
+    emit!(AlbumCreated {
+        album: album.key(),
+        creator: album.creator,
+        title: album.title.clone(),
+        released_at: album.released_at,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Album created: {}", album.title);
+    Ok(())
+}
+
+pub fn add_track_to_album(
+    ctx: Context<AddTrackToAlbum>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    append_album_track(
+        &mut ctx.accounts.album,
+        &mut ctx.accounts.album_track,
+        &mut ctx.accounts.track,
+        clock.unix_timestamp,
+    )?;
+
+    msg!("Track added to album: {}", ctx.accounts.album.title);
+    Ok(())
+}
+
+/// Puts `track` at the end of `album` and points the track at the album.
+pub(crate) fn append_album_track(
+    album: &mut Account<Album>,
+    album_track: &mut Account<AlbumTrack>,
+    track: &mut Account<Track>,
+    now: i64,
+) -> Result<()> {
+    require!(track.album_key.is_none(), SpotifyError::TrackAlreadyInAlbum);
+
+    album_track.album = album.key();
+    album_track.track = track.key();
+    album_track.position = album.track_count;
+    album_track.added_at = now;
+
+    track.album_key = Some(album.key());
+    track.album = album.title.clone();
+
+    // Update album track count
+    album.track_count = album.track_count.checked_add(1)
+        .ok_or(SpotifyError::ArithmeticOverflow)?;
+
+    emit!(TrackAddedToAlbum {
+        album: album.key(),
+        track: track.key(),
+        position: album_track.position,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(title: String)]
+pub struct CreateAlbum<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Album::MAX_SIZE,
+        seeds = [b"album", authority.key().as_ref(), title.as_bytes()],
+        bump
+    )]
+    pub album: Account<'info, Album>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddTrackToAlbum<'info> {
+    #[account(
+        mut,
+        seeds = [b"album", album.creator.as_ref(), album.title.as_bytes()],
+        bump,
+        constraint = album.creator == authority.key() @ SpotifyError::Unauthorized
+    )]
+    pub album: Account<'info, Album>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AlbumTrack::MAX_SIZE,
+        seeds = [b"album_track", album.key().as_ref(), &album.track_count.to_le_bytes()],
+        bump
+    )]
+    pub album_track: Account<'info, AlbumTrack>,
+
+    #[account(
+        mut,
+        seeds = [b"track", track.title.as_bytes(), track.artist.as_bytes()],
+        bump,
+        constraint = track.created_by == authority.key() @ SpotifyError::Unauthorized
+    )]
+    pub track: Box<Account<'info, Track>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
 use crate::state::*;
 use crate::errors::SpotifyError;
+use crate::events::*;
 
 #[derive(Accounts)]
 pub struct GenerateUserInsights<'info> {
@@ -22,6 +24,12 @@ pub struct GenerateUserInsights<'info> {
     )]
     pub user_stats: Account<'info, UserStats>,
 
+    #[account(
+        seeds = [b"user_profile", user.key().as_ref()],
+        bump
+    )]
+    pub user_profile: Box<Account<'info, UserProfile>>,
+
     #[account(mut)]
     pub user: Signer<'info>,
 
@@ -33,19 +41,82 @@ pub struct GenerateUserInsights<'info> {
 pub struct CreateRecommendation<'info> {
     #[account(
         init,
-        payer = user,
+        payer = payer,
         space = 8 + Recommendation::MAX_SIZE,
         seeds = [b"recommendation", user.key().as_ref(), target.as_ref(), &recommendation_type.to_le_bytes()],
         bump
     )]
     pub recommendation: Account<'info, Recommendation>,
 
+    /// CHECK: Must be the recommended account, its type is validated in the handler
+    #[account(address = target @ SpotifyError::InvalidRecommendationTarget)]
+    pub target_account: UncheckedAccount<'info>,
+
+    /// CHECK: The user receiving the recommendation, authorised in the handler
+    pub user: UncheckedAccount<'info>,
+
+    // Required when a recommender pushes a recommendation on behalf of `user`
+    #[account(seeds = [b"config"], bump)]
+    pub config: Option<Account<'info, ProgramConfig>>,
+
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub payer: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct MarkRecommendationViewed<'info> {
+    #[account(
+        mut,
+        seeds = [b"recommendation", user.key().as_ref(), recommendation.target.as_ref(), &recommendation.recommendation_type.to_le_bytes()],
+        bump,
+        has_one = user
+    )]
+    pub recommendation: Account<'info, Recommendation>,
+
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DismissRecommendation<'info> {
+    #[account(
+        mut,
+        close = payer,
+        seeds = [b"recommendation", user.key().as_ref(), recommendation.target.as_ref(), &recommendation.recommendation_type.to_le_bytes()],
+        bump,
+        has_one = user,
+        has_one = payer
+    )]
+    pub recommendation: Account<'info, Recommendation>,
+
+    /// CHECK: Receives the rent of the dismissed recommendation, checked against `recommendation.payer`
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireRecommendation<'info> {
+    #[account(
+        mut,
+        close = payer,
+        seeds = [b"recommendation", recommendation.user.as_ref(), recommendation.target.as_ref(), &recommendation.recommendation_type.to_le_bytes()],
+        bump,
+        has_one = payer
+    )]
+    pub recommendation: Account<'info, Recommendation>,
+
+    /// CHECK: Receives the rent of the expired recommendation, checked against `recommendation.payer`
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    pub caller: Signer<'info>,
+}
+
+/// Remaining accounts must be pairs of the caller's `TrackPlay` followed by the
+/// `Track` it refers to. Insights are derived from the pairs that are passed in.
 pub fn generate_user_insights(
     ctx: Context<GenerateUserInsights>,
 ) -> Result<()> {
@@ -60,18 +131,27 @@ pub fn generate_user_insights(
         user_stats.playlists_created = 0;
         user_stats.total_likes_received = 0;
         user_stats.total_plays = 0;
+        user_stats.total_tips_received = 0;
         user_stats.last_active = clock.unix_timestamp;
     }
 
+    let plays = load_listening_history(&ctx.accounts.user.key(), ctx.remaining_accounts)?;
+
     // Calculate insights based on user activity
     user_insights.user = ctx.accounts.user.key();
-    user_insights.total_listening_time = calculate_total_listening_time(&user_stats)?;
-    user_insights.favorite_genre = determine_favorite_genre(&ctx.accounts.user.key())?;
-    user_insights.most_played_track = find_most_played_track(&ctx.accounts.user.key())?;
-    user_insights.discovery_score = calculate_discovery_score(&user_stats)?;
-    user_insights.social_engagement = calculate_social_engagement(&user_stats)?;
+    user_insights.total_listening_time = calculate_total_listening_time(&plays)?;
+    user_insights.favorite_genre = determine_favorite_genre(&plays);
+    user_insights.most_played_track = find_most_played_track(&plays);
+    user_insights.discovery_score = calculate_discovery_score(&plays)?;
+    user_insights.social_engagement = calculate_social_engagement(&ctx.accounts.user_profile, user_stats)?;
     user_insights.generated_at = clock.unix_timestamp;
 
+    emit!(UserInsightsGenerated {
+        user: user_insights.user,
+        total_listening_time: user_insights.total_listening_time,
+        timestamp: clock.unix_timestamp,
+    });
+
     msg!("User insights generated");
     Ok(())
 }
@@ -85,9 +165,26 @@ pub fn create_recommendation(
 ) -> Result<()> {
     let recommendation = &mut ctx.accounts.recommendation;
     let clock = Clock::get()?;
+    let payer = ctx.accounts.payer.key();
+
+    // Users may recommend to themselves, anyone else must be a registered recommender
+    if payer != ctx.accounts.user.key() {
+        let config = ctx.accounts.config.as_ref().ok_or(SpotifyError::Unauthorized)?;
+        require!(config.is_recommender(&payer), SpotifyError::Unauthorized);
+    }
 
     require!(reason.len() <= 128, SpotifyError::ReasonTooLong);
-    require!(score >= 0.0 && score <= 1.0, SpotifyError::InvalidScore);
+    require!((0.0..=1.0).contains(&score), SpotifyError::InvalidScore);
+
+    // The target must be an account of the recommended type
+    let target_account = ctx.accounts.target_account.to_account_info();
+    let discriminator = recommendation_target_discriminator(recommendation_type)
+        .ok_or(SpotifyError::InvalidRecommendationType)?;
+    require!(
+        target_account.owner == &crate::ID
+            && target_account.try_borrow_data()?.starts_with(discriminator),
+        SpotifyError::InvalidRecommendationTarget
+    );
 
     recommendation.user = ctx.accounts.user.key();
     recommendation.recommendation_type = recommendation_type;
@@ -96,28 +193,184 @@ pub fn create_recommendation(
     recommendation.reason = reason;
     recommendation.created_at = clock.unix_timestamp;
     recommendation.is_viewed = false;
+    recommendation.payer = payer;
+
+    emit!(RecommendationCreated {
+        recommendation: recommendation.key(),
+        user: recommendation.user,
+        recommendation_type,
+        target,
+        score,
+        timestamp: clock.unix_timestamp,
+    });
 
     msg!("Recommendation created for user");
     Ok(())
 }
 
-// Helper functions (simplified for demonstration)
-fn calculate_total_listening_time(_user_stats: &UserStats) -> Result<u64> {
-    Ok(0)
+pub fn mark_recommendation_viewed(
+    ctx: Context<MarkRecommendationViewed>,
+) -> Result<()> {
+    let recommendation = &mut ctx.accounts.recommendation;
+    let clock = Clock::get()?;
+
+    require!(
+        !recommendation.is_expired(clock.unix_timestamp),
+        SpotifyError::RecommendationExpired
+    );
+
+    recommendation.is_viewed = true;
+
+    emit!(RecommendationViewed {
+        recommendation: recommendation.key(),
+        user: recommendation.user,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Recommendation marked as viewed");
+    Ok(())
+}
+
+pub fn dismiss_recommendation(
+    ctx: Context<DismissRecommendation>,
+) -> Result<()> {
+    let recommendation = &ctx.accounts.recommendation;
+    let clock = Clock::get()?;
+
+    emit!(RecommendationDismissed {
+        recommendation: recommendation.key(),
+        user: recommendation.user,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Recommendation dismissed");
+    Ok(())
+}
+
+/// Anyone may clean up an expired recommendation; the rent always goes back to whoever paid it.
+pub fn expire_recommendation(
+    ctx: Context<ExpireRecommendation>,
+) -> Result<()> {
+    let recommendation = &ctx.accounts.recommendation;
+    let clock = Clock::get()?;
+
+    require!(
+        recommendation.is_expired(clock.unix_timestamp),
+        SpotifyError::RecommendationNotExpired
+    );
+
+    emit!(RecommendationExpired {
+        recommendation: recommendation.key(),
+        user: recommendation.user,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Recommendation expired");
+    Ok(())
 }
 
-fn determine_favorite_genre(_user: &Pubkey) -> Result<String> {
-    Ok("Unknown".to_string())
+// Helper functions
+
+fn recommendation_target_discriminator(recommendation_type: u8) -> Option<&'static [u8]> {
+    match recommendation_type {
+        Recommendation::TYPE_TRACK => Some(Track::DISCRIMINATOR),
+        Recommendation::TYPE_PLAYLIST => Some(Playlist::DISCRIMINATOR),
+        Recommendation::TYPE_USER => Some(UserProfile::DISCRIMINATOR),
+        _ => None,
+    }
 }
 
-fn find_most_played_track(_user: &Pubkey) -> Result<Option<Pubkey>> {
-    Ok(None)
+/// Validates the `(TrackPlay, Track)` pairs in `accounts` and returns them with the track address.
+fn load_listening_history(user: &Pubkey, accounts: &[AccountInfo]) -> Result<Vec<(Pubkey, TrackPlay, Track)>> {
+    let pairs = accounts.chunks_exact(2);
+    require!(pairs.remainder().is_empty(), SpotifyError::InvalidAccount);
+
+    let mut plays: Vec<(Pubkey, TrackPlay, Track)> = Vec::with_capacity(pairs.len());
+    for pair in pairs {
+        let (play_info, track_info) = (&pair[0], &pair[1]);
+        require!(
+            play_info.owner == &crate::ID && track_info.owner == &crate::ID,
+            SpotifyError::InvalidAccount
+        );
+
+        let track_play = TrackPlay::try_deserialize(&mut &play_info.try_borrow_data()?[..])?;
+        let track = Track::try_deserialize(&mut &track_info.try_borrow_data()?[..])?;
+        require!(
+            track_play.user == *user && track_play.track == track_info.key(),
+            SpotifyError::InvalidAccount
+        );
+
+        // Each track may only be counted once
+        require!(
+            !plays.iter().any(|(key, _, _)| *key == track_info.key()),
+            SpotifyError::InvalidAccount
+        );
+
+        plays.push((track_info.key(), track_play, track));
+    }
+
+    Ok(plays)
 }
 
-fn calculate_discovery_score(_user_stats: &UserStats) -> Result<f32> {
-    Ok(0.5)
+fn calculate_total_listening_time(plays: &[(Pubkey, TrackPlay, Track)]) -> Result<u64> {
+    plays.iter().try_fold(0u64, |total, (_, track_play, _)| {
+        total.checked_add(track_play.total_duration)
+            .ok_or(SpotifyError::ArithmeticOverflow.into())
+    })
 }
 
-fn calculate_social_engagement(_user_stats: &UserStats) -> Result<f32> {
-    Ok(0.5)
+/// Genre with the most listening time, ties broken by play count.
+fn determine_favorite_genre(plays: &[(Pubkey, TrackPlay, Track)]) -> String {
+    let mut genres: Vec<(&str, u64, u64)> = Vec::new();
+    for (_, track_play, track) in plays {
+        if track.genre.is_empty() {
+            continue;
+        }
+        match genres.iter_mut().find(|(genre, _, _)| *genre == track.genre) {
+            Some((_, duration, count)) => {
+                *duration = duration.saturating_add(track_play.total_duration);
+                *count = count.saturating_add(track_play.play_count);
+            }
+            None => genres.push((&track.genre, track_play.total_duration, track_play.play_count)),
+        }
+    }
+
+    genres
+        .into_iter()
+        .max_by_key(|(_, duration, count)| (*duration, *count))
+        .map(|(genre, _, _)| genre.to_string())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+fn find_most_played_track(plays: &[(Pubkey, TrackPlay, Track)]) -> Option<Pubkey> {
+    plays
+        .iter()
+        .filter(|(_, track_play, _)| track_play.play_count > 0)
+        .max_by_key(|(_, track_play, _)| (track_play.play_count, track_play.total_duration))
+        .map(|(key, _, _)| *key)
+}
+
+/// Ratio of distinct tracks to total plays: 1.0 means every play was a new track.
+fn calculate_discovery_score(plays: &[(Pubkey, TrackPlay, Track)]) -> Result<f32> {
+    let total_plays = plays.iter().try_fold(0u64, |total, (_, track_play, _)| {
+        total.checked_add(track_play.play_count)
+            .ok_or(SpotifyError::ArithmeticOverflow)
+    })?;
+    if total_plays == 0 {
+        return Ok(0.0);
+    }
+
+    Ok((plays.len() as f32 / total_plays as f32).min(1.0))
+}
+
+/// Saturating score in [0, 1) that reaches 0.5 at `UserInsights::SOCIAL_ENGAGEMENT_MIDPOINT` interactions.
+fn calculate_social_engagement(user_profile: &UserProfile, user_stats: &UserStats) -> Result<f32> {
+    let interactions = user_profile.followers_count
+        .checked_add(user_profile.following_count)
+        .and_then(|total| total.checked_add(user_stats.total_likes_received))
+        .and_then(|total| total.checked_add(user_stats.playlists_created))
+        .and_then(|total| total.checked_add(user_stats.tracks_created))
+        .ok_or(SpotifyError::ArithmeticOverflow)? as f32;
+
+    Ok(interactions / (interactions + UserInsights::SOCIAL_ENGAGEMENT_MIDPOINT))
 }
@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::SpotifyError;
+use crate::events::*;
+use crate::instructions::playlist::close_account;
+
+pub fn create_artist_profile(
+    ctx: Context<CreateArtistProfile>,
+    name: String,
+) -> Result<()> {
+    let artist_profile = &mut ctx.accounts.artist_profile;
+    let clock = Clock::get()?;
+
+    require!(name.len() <= 64, SpotifyError::ArtistNameTooLong);
+    require!(!name.is_empty(), SpotifyError::ArtistNameEmpty);
+
+    artist_profile.authority = ctx.accounts.authority.key();
+    artist_profile.user_profile = ctx.accounts.user_profile.key();
+    artist_profile.name = name;
+    artist_profile.is_verified = false;
+    artist_profile.verified_at = 0;
+    artist_profile.created_at = clock.unix_timestamp;
+
+    emit!(ArtistProfileCreated {
+        artist_profile: artist_profile.key(),
+        authority: artist_profile.authority,
+        user_profile: artist_profile.user_profile,
+        name: artist_profile.name.clone(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Artist profile created: {}", artist_profile.name);
+    Ok(())
+}
+
+/// Verifying claims the artist's name for their profile, and revoking verification
+/// releases it.
+pub fn verify_artist(
+    ctx: Context<VerifyArtist>,
+    is_verified: bool,
+) -> Result<()> {
+    let artist_profile = &mut ctx.accounts.artist_profile;
+    let artist_name_claim = &mut ctx.accounts.artist_name_claim;
+    let clock = Clock::get()?;
+
+    // A fresh claim has no owner yet
+    let claim_owner = artist_name_claim.artist_profile;
+    if is_verified {
+        require!(
+            claim_owner == Pubkey::default() || claim_owner == artist_profile.key(),
+            SpotifyError::ArtistNameTaken
+        );
+        if claim_owner == Pubkey::default() {
+            artist_name_claim.artist_profile = artist_profile.key();
+            artist_name_claim.name = artist_profile.name.clone();
+            artist_name_claim.created_at = clock.unix_timestamp;
+        }
+    } else if claim_owner == Pubkey::default() || claim_owner == artist_profile.key() {
+        close_account(&artist_name_claim.to_account_info(), &ctx.accounts.admin.to_account_info())?;
+    }
+
+    artist_profile.is_verified = is_verified;
+    if is_verified {
+        artist_profile.verified_at = clock.unix_timestamp;
+    }
+
+    emit!(ArtistVerificationUpdated {
+        artist_profile: artist_profile.key(),
+        is_verified,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Artist {} verified: {}", artist_profile.name, is_verified);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateArtistProfile<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ArtistProfile::MAX_SIZE,
+        seeds = [b"artist_profile", authority.key().as_ref()],
+        bump
+    )]
+    pub artist_profile: Account<'info, ArtistProfile>,
+
+    #[account(
+        seeds = [b"user_profile", authority.key().as_ref()],
+        bump,
+        constraint = user_profile.authority == authority.key() @ SpotifyError::Unauthorized
+    )]
+    pub user_profile: Box<Account<'info, UserProfile>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyArtist<'info> {
+    #[account(
+        mut,
+        seeds = [b"artist_profile", artist_profile.authority.as_ref()],
+        bump
+    )]
+    pub artist_profile: Account<'info, ArtistProfile>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + ArtistNameClaim::MAX_SIZE,
+        seeds = [b"artist_name".as_ref(), ArtistNameClaim::seed(&artist_profile.name).as_ref()],
+        bump
+    )]
+    pub artist_name_claim: Account<'info, ArtistNameClaim>,
+
+    #[account(
+        seeds = [b"config"],
+        bump,
+        has_one = admin @ SpotifyError::Unauthorized
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
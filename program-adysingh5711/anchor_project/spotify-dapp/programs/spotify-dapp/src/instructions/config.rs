@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::SpotifyError;
+use crate::events::*;
+use crate::program::SpotifyProgram;
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ProgramConfig::MAX_SIZE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()) @ SpotifyError::Unauthorized)]
+    pub program: Program<'info, SpotifyProgram>,
+
+    // Only the upgrade authority may create the config, so it cannot be front-run
+    #[account(constraint = program_data.upgrade_authority_address == Some(authority.key()) @ SpotifyError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRecommenders<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump,
+        has_one = admin @ SpotifyError::Unauthorized
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePricing<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump,
+        has_one = admin @ SpotifyError::Unauthorized
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn initialize_config(
+    ctx: Context<InitializeConfig>,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    config.admin = ctx.accounts.authority.key();
+    config.recommenders = Vec::new();
+    config.created_at = clock.unix_timestamp;
+    config.platform_fee_bps = 0;
+    config.fee_recipient = ctx.accounts.authority.key();
+    config.subscription_price_lamports = 0;
+
+    emit!(ConfigInitialized {
+        config: config.key(),
+        admin: config.admin,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Program config initialized");
+    Ok(())
+}
+
+pub fn add_recommender(
+    ctx: Context<UpdateRecommenders>,
+    recommender: Pubkey,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    require!(!config.is_recommender(&recommender), SpotifyError::RecommenderAlreadyAdded);
+    require!(
+        config.recommenders.len() < ProgramConfig::MAX_RECOMMENDERS,
+        SpotifyError::TooManyRecommenders
+    );
+
+    config.recommenders.push(recommender);
+
+    emit!(RecommenderAdded {
+        recommender,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Recommender added: {}", recommender);
+    Ok(())
+}
+
+pub fn remove_recommender(
+    ctx: Context<UpdateRecommenders>,
+    recommender: Pubkey,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    let index = config.recommenders.iter()
+        .position(|key| *key == recommender)
+        .ok_or(SpotifyError::RecommenderNotFound)?;
+    config.recommenders.remove(index);
+
+    emit!(RecommenderRemoved {
+        recommender,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Recommender removed: {}", recommender);
+    Ok(())
+}
+
+pub fn set_platform_fee(
+    ctx: Context<UpdatePricing>,
+    platform_fee_bps: u16,
+    fee_recipient: Pubkey,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    require!(
+        platform_fee_bps <= ProgramConfig::MAX_PLATFORM_FEE_BPS,
+        SpotifyError::InvalidPlatformFee
+    );
+
+    config.platform_fee_bps = platform_fee_bps;
+    config.fee_recipient = fee_recipient;
+
+    emit!(PlatformFeeUpdated {
+        platform_fee_bps,
+        fee_recipient,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Platform fee set to {} bps", platform_fee_bps);
+    Ok(())
+}
+
+pub fn set_subscription_price(
+    ctx: Context<UpdatePricing>,
+    subscription_price_lamports: u64,
+) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let clock = Clock::get()?;
+
+    config.subscription_price_lamports = subscription_price_lamports;
+
+    emit!(SubscriptionPriceUpdated {
+        subscription_price_lamports,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Subscription price set to {} lamports", subscription_price_lamports);
+    Ok(())
+}
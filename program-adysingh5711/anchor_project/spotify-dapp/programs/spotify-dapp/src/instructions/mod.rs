@@ -4,6 +4,10 @@ pub mod track;
 pub mod social;
 pub mod search;
 pub mod analytics;
+pub mod config;
+pub mod payments;
+pub mod album;
+pub mod artist;
 
 pub use user::*;
 pub use playlist::*;
@@ -11,3 +15,7 @@ pub use track::*;
 pub use social::*;
 pub use search::*;
 pub use analytics::*;
+pub use config::*;
+pub use payments::*;
+pub use album::*;
+pub use artist::*;
@@ -0,0 +1,282 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::SpotifyError;
+use crate::events::*;
+
+/// Paid tracks need the escrow, so creators call this once before setting a price.
+pub fn init_creator_escrow(
+    ctx: Context<InitCreatorEscrow>,
+) -> Result<()> {
+    let creator_escrow = &mut ctx.accounts.creator_escrow;
+    let clock = Clock::get()?;
+
+    creator_escrow.creator = ctx.accounts.creator.key();
+    creator_escrow.total_earned = 0;
+    creator_escrow.total_withdrawn = 0;
+    creator_escrow.created_at = clock.unix_timestamp;
+
+    emit!(CreatorEscrowCreated {
+        creator_escrow: creator_escrow.key(),
+        creator: creator_escrow.creator,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Creator escrow created for: {}", creator_escrow.creator);
+    Ok(())
+}
+
+pub fn withdraw_earnings(
+    ctx: Context<WithdrawEarnings>,
+) -> Result<()> {
+    let creator_escrow = &mut ctx.accounts.creator_escrow;
+    let creator = &ctx.accounts.creator;
+    let clock = Clock::get()?;
+
+    let amount = creator_escrow.available();
+    require!(amount > 0, SpotifyError::NoEarningsToWithdraw);
+
+    // The escrow is owned by the program, so lamports are moved directly
+    let escrow_info = creator_escrow.to_account_info();
+    **escrow_info.try_borrow_mut_lamports()? = escrow_info.lamports().checked_sub(amount)
+        .ok_or(SpotifyError::ArithmeticUnderflow)?;
+    **creator.try_borrow_mut_lamports()? = creator.lamports().checked_add(amount)
+        .ok_or(SpotifyError::ArithmeticOverflow)?;
+
+    // Update withdrawn total
+    creator_escrow.total_withdrawn = creator_escrow.total_withdrawn.checked_add(amount)
+        .ok_or(SpotifyError::ArithmeticOverflow)?;
+
+    emit!(EarningsWithdrawn {
+        creator_escrow: creator_escrow.key(),
+        creator: creator.key(),
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Earnings withdrawn: {} lamports", amount);
+    Ok(())
+}
+
+pub fn subscribe(
+    ctx: Context<Subscribe>,
+    periods: u8,
+) -> Result<()> {
+    let subscription = &mut ctx.accounts.subscription;
+    let treasury = &mut ctx.accounts.treasury;
+    let user = &ctx.accounts.user;
+    let clock = Clock::get()?;
+
+    require!(
+        (1..=Subscription::MAX_PERIODS).contains(&periods),
+        SpotifyError::InvalidSubscriptionPeriod
+    );
+    let price = ctx.accounts.config.subscription_price_lamports;
+    require!(price > 0, SpotifyError::SubscriptionPriceNotSet);
+
+    let amount = price.checked_mul(periods as u64)
+        .ok_or(SpotifyError::ArithmeticOverflow)?;
+    transfer_lamports(user, &treasury.to_account_info(), &ctx.accounts.system_program, amount)?;
+
+    // Initialize on the first subscription
+    if subscription.user == Pubkey::default() {
+        subscription.user = user.key();
+        subscription.expires_at = clock.unix_timestamp;
+        subscription.total_paid = 0;
+        subscription.created_at = clock.unix_timestamp;
+    }
+    if treasury.created_at == 0 {
+        treasury.created_at = clock.unix_timestamp;
+    }
+
+    // Extend from the current expiry, or from now if it already lapsed
+    let start = subscription.expires_at.max(clock.unix_timestamp);
+    subscription.expires_at = start.checked_add(Subscription::PERIOD_SECONDS * periods as i64)
+        .ok_or(SpotifyError::ArithmeticOverflow)?;
+    subscription.total_paid = subscription.total_paid.checked_add(amount)
+        .ok_or(SpotifyError::ArithmeticOverflow)?;
+    treasury.total_collected = treasury.total_collected.checked_add(amount)
+        .ok_or(SpotifyError::ArithmeticOverflow)?;
+
+    emit!(Subscribed {
+        subscription: subscription.key(),
+        user: user.key(),
+        periods,
+        amount,
+        expires_at: subscription.expires_at,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Subscribed until {}", subscription.expires_at);
+    Ok(())
+}
+
+/// Moves subscription revenue to the config admin, keeping the treasury rent-exempt.
+pub fn withdraw_treasury(
+    ctx: Context<WithdrawTreasury>,
+    amount: u64,
+) -> Result<()> {
+    let treasury_info = ctx.accounts.treasury.to_account_info();
+    let admin = &ctx.accounts.admin;
+    let clock = Clock::get()?;
+
+    let rent = Rent::get()?.minimum_balance(treasury_info.data_len());
+    let available = treasury_info.lamports().saturating_sub(rent);
+    require!(amount > 0 && amount <= available, SpotifyError::InsufficientTreasuryFunds);
+
+    // The treasury is owned by the program, so lamports are moved directly
+    **treasury_info.try_borrow_mut_lamports()? = treasury_info.lamports().checked_sub(amount)
+        .ok_or(SpotifyError::ArithmeticUnderflow)?;
+    **admin.try_borrow_mut_lamports()? = admin.lamports().checked_add(amount)
+        .ok_or(SpotifyError::ArithmeticOverflow)?;
+
+    emit!(TreasuryWithdrawn {
+        treasury: treasury_info.key(),
+        admin: admin.key(),
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Treasury withdrawn: {} lamports", amount);
+    Ok(())
+}
+
+/// Premium tracks need an active subscription, except for their creator.
+pub(crate) fn require_track_access(
+    track: &Track,
+    listener: Pubkey,
+    subscription: Option<&Account<Subscription>>,
+    now: i64,
+) -> Result<()> {
+    if !track.is_premium || listener == track.created_by {
+        return Ok(());
+    }
+
+    require!(
+        subscription.is_some_and(|subscription| subscription.is_active(now)),
+        SpotifyError::SubscriptionRequired
+    );
+    Ok(())
+}
+
+/// Splits `price` between the platform fee recipient and the creator escrow.
+pub(crate) fn collect_play_payment<'info>(
+    price: u64,
+    listener: &Signer<'info>,
+    creator_escrow: Option<&mut Account<'info, CreatorEscrow>>,
+    config: Option<&Account<'info, ProgramConfig>>,
+    fee_recipient: Option<&SystemAccount<'info>>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let (Some(creator_escrow), Some(config)) = (creator_escrow, config) else {
+        return err!(SpotifyError::PaymentAccountsMissing);
+    };
+
+    let fee = config.platform_fee(price).ok_or(SpotifyError::ArithmeticOverflow)?;
+    let creator_amount = price.checked_sub(fee).ok_or(SpotifyError::ArithmeticUnderflow)?;
+
+    if fee > 0 {
+        let fee_recipient = fee_recipient.ok_or(SpotifyError::PaymentAccountsMissing)?;
+        require_keys_eq!(fee_recipient.key(), config.fee_recipient, SpotifyError::InvalidFeeRecipient);
+        transfer_lamports(listener, &fee_recipient.to_account_info(), system_program, fee)?;
+    }
+
+    if creator_amount > 0 {
+        transfer_lamports(listener, &creator_escrow.to_account_info(), system_program, creator_amount)?;
+        creator_escrow.total_earned = creator_escrow.total_earned.checked_add(creator_amount)
+            .ok_or(SpotifyError::ArithmeticOverflow)?;
+    }
+
+    Ok(())
+}
+
+fn transfer_lamports<'info>(
+    from: &Signer<'info>,
+    to: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_context = CpiContext::new(
+        system_program.to_account_info(),
+        anchor_lang::system_program::Transfer {
+            from: from.to_account_info(),
+            to: to.clone(),
+        },
+    );
+    anchor_lang::system_program::transfer(cpi_context, amount)
+}
+
+#[derive(Accounts)]
+pub struct InitCreatorEscrow<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + CreatorEscrow::MAX_SIZE,
+        seeds = [b"creator_escrow", creator.key().as_ref()],
+        bump
+    )]
+    pub creator_escrow: Account<'info, CreatorEscrow>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawEarnings<'info> {
+    #[account(
+        mut,
+        seeds = [b"creator_escrow", creator.key().as_ref()],
+        bump,
+        has_one = creator @ SpotifyError::Unauthorized
+    )]
+    pub creator_escrow: Account<'info, CreatorEscrow>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    #[account(mut, seeds = [b"treasury"], bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        seeds = [b"config"],
+        bump,
+        has_one = admin @ SpotifyError::Unauthorized
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Subscribe<'info> {
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + Subscription::MAX_SIZE,
+        seeds = [b"subscription", user.key().as_ref()],
+        bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + Treasury::MAX_SIZE,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, ProgramConfig>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
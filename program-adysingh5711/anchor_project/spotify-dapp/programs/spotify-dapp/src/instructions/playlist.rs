@@ -1,9 +1,14 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::errors::SpotifyError;
+use crate::events::*;
+use crate::instructions::payments::require_track_access;
+use crate::instructions::search::{close_search_entries, index_new_target, prune_search_entries};
 
-pub fn create_playlist(
-    ctx: Context<CreatePlaylist>,
+/// Remaining accounts are the `SearchIndex` PDAs of the name terms to index, in
+/// `SearchIndex::tokenize` order. Private playlists take none.
+pub fn create_playlist<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreatePlaylist<'info>>,
     name: String,
     description: String,
     is_public: bool,
@@ -11,10 +16,11 @@ pub fn create_playlist(
 ) -> Result<()> {
     let playlist = &mut ctx.accounts.playlist;
     let user_stats = &mut ctx.accounts.user_stats;
+    let activity_feed = &mut ctx.accounts.activity_feed;
     let clock = Clock::get()?;
 
     require!(name.len() <= 64, SpotifyError::PlaylistNameTooLong);
-    require!(name.len() > 0, SpotifyError::PlaylistNameEmpty);
+    require!(!name.is_empty(), SpotifyError::PlaylistNameEmpty);
     require!(description.len() <= 256, SpotifyError::PlaylistDescriptionTooLong);
 
     playlist.authority = ctx.accounts.authority.key();
@@ -24,19 +30,212 @@ pub fn create_playlist(
     playlist.is_collaborative = is_collaborative;
     playlist.tracks_count = 0;
     playlist.likes_count = 0;
+    playlist.collaborators_count = 0;
     playlist.plays_count = 0;
     playlist.created_at = clock.unix_timestamp;
     playlist.updated_at = clock.unix_timestamp;
 
+    // Index the chosen search terms, private playlists stay out of search
+    let search_terms = if playlist.is_public { SearchIndex::tokenize(&[&playlist.name]) } else { Vec::new() };
+    playlist.search_entries_count = index_new_target(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        ctx.remaining_accounts,
+        &search_terms,
+        SearchIndex::TARGET_PLAYLIST,
+        playlist.key(),
+        clock.unix_timestamp,
+    )?;
+
     // Update user stats
     user_stats.playlists_created = user_stats.playlists_created.checked_add(1)
         .ok_or(SpotifyError::ArithmeticOverflow)?;
     user_stats.last_active = clock.unix_timestamp;
 
+    // Append to activity feed
+    activity_feed.record(
+        ctx.accounts.authority.key(),
+        ActivityFeed::ACTIVITY_PLAYLIST_CREATED,
+        playlist.key(),
+        format!("Created playlist: {}", playlist.name),
+        clock.unix_timestamp,
+    );
+
+    emit!(PlaylistCreated {
+        playlist: playlist.key(),
+        authority: playlist.authority,
+        name: playlist.name.clone(),
+        is_public: playlist.is_public,
+        is_collaborative: playlist.is_collaborative,
+        timestamp: clock.unix_timestamp,
+    });
+
     msg!("Playlist created: {}", playlist.name);
     Ok(())
 }
 
+/// The playlist name is part of the PDA seeds, so it cannot be changed here.
+/// Visibility and collaboration settings are reserved for the owner. Making the
+/// playlist private takes every `SearchIndex` entry as remaining accounts and closes them.
+pub fn update_playlist(
+    ctx: Context<UpdatePlaylist>,
+    description: Option<String>,
+    is_public: Option<bool>,
+    is_collaborative: Option<bool>,
+) -> Result<()> {
+    let playlist = &mut ctx.accounts.playlist;
+    let clock = Clock::get()?;
+    let authority = ctx.accounts.authority.key();
+
+    require!(
+        has_playlist_permission(
+            playlist,
+            authority,
+            ctx.accounts.playlist_collaborator.as_deref(),
+            PlaylistCollaborator::PERMISSION_EDIT_INFO,
+        ),
+        SpotifyError::NoPermissionToEditPlaylist
+    );
+
+    if let Some(description_text) = description {
+        require!(description_text.len() <= 256, SpotifyError::PlaylistDescriptionTooLong);
+        playlist.description = description_text;
+    }
+
+    if is_public.is_some() || is_collaborative.is_some() {
+        require!(playlist.authority == authority, SpotifyError::NoPermissionToEditPlaylist);
+    }
+
+    if let Some(public) = is_public {
+        // Private playlists have no search terms, so every entry is closed
+        if !public {
+            playlist.search_entries_count = prune_search_entries(
+                playlist.key(),
+                playlist.search_entries_count,
+                ctx.remaining_accounts,
+                &[],
+                &ctx.accounts.authority.to_account_info(),
+            )?;
+        }
+        playlist.is_public = public;
+    }
+
+    if let Some(collaborative) = is_collaborative {
+        playlist.is_collaborative = collaborative;
+    }
+
+    playlist.updated_at = clock.unix_timestamp;
+
+    emit!(PlaylistUpdated {
+        playlist: playlist.key(),
+        updated_by: authority,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Playlist updated: {}", playlist.name);
+    Ok(())
+}
+
+/// Remaining accounts must hold every `PlaylistTrack` of the playlist, each followed
+/// by its `added_by` account which receives the entry's rent and by its `Track`, then
+/// every `PlaylistLike` followed by the liking user, then every `PlaylistCollaborator`,
+/// then every `SearchIndex` entry.
+pub fn delete_playlist<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DeletePlaylist<'info>>,
+) -> Result<()> {
+    let playlist = &ctx.accounts.playlist;
+    let user_stats = &mut ctx.accounts.user_stats;
+    let authority_info = ctx.accounts.authority.to_account_info();
+    let clock = Clock::get()?;
+
+    let remaining = ctx.remaining_accounts;
+    let tracks_len = playlist.tracks_count.checked_mul(3).ok_or(SpotifyError::ArithmeticOverflow)?;
+    let likes_len = playlist.likes_count.checked_mul(2).ok_or(SpotifyError::ArithmeticOverflow)?;
+    let expected = tracks_len
+        .checked_add(likes_len)
+        .and_then(|len| len.checked_add(playlist.collaborators_count))
+        .ok_or(SpotifyError::ArithmeticOverflow)?;
+    require!(remaining.len() as u64 >= expected, SpotifyError::PlaylistNotEmpty);
+
+    let (track_accounts, rest) = remaining.split_at(tracks_len as usize);
+    let (like_accounts, rest) = rest.split_at(likes_len as usize);
+    let (collaborator_accounts, search_accounts) = rest.split_at(playlist.collaborators_count as usize);
+
+    let mut seen = vec![false; playlist.tracks_count as usize];
+    for triple in track_accounts.chunks(3) {
+        let (entry_info, added_by_info, track_info) = (&triple[0], &triple[1], &triple[2]);
+        require!(entry_info.owner == &crate::ID && entry_info.is_writable, SpotifyError::InvalidAccount);
+
+        let entry = PlaylistTrack::try_deserialize(&mut &entry_info.try_borrow_data()?[..])?;
+        require!(entry.playlist == playlist.key(), SpotifyError::PlaylistTracksMismatch);
+        require!(entry.position < playlist.tracks_count, SpotifyError::PlaylistTracksMismatch);
+        require!(!seen[entry.position as usize], SpotifyError::PlaylistTracksMismatch);
+        seen[entry.position as usize] = true;
+
+        require!(
+            added_by_info.key() == entry.added_by && added_by_info.is_writable,
+            SpotifyError::InvalidAccount
+        );
+        require!(
+            track_info.key() == entry.track && track_info.owner == &crate::ID && track_info.is_writable,
+            SpotifyError::InvalidAccount
+        );
+
+        // Decrement playlists count on track
+        let mut track = Track::try_deserialize(&mut &track_info.try_borrow_data()?[..])?;
+        track.playlists_count = track.playlists_count.checked_sub(1)
+            .ok_or(SpotifyError::ArithmeticUnderflow)?;
+        track.try_serialize(&mut &mut track_info.try_borrow_mut_data()?[..])?;
+
+        close_account(entry_info, added_by_info)?;
+    }
+
+    // Closed accounts are no longer program-owned, so passing one twice fails the owner check
+    for pair in like_accounts.chunks(2) {
+        let (like_info, user_info) = (&pair[0], &pair[1]);
+        require!(like_info.owner == &crate::ID && like_info.is_writable, SpotifyError::InvalidAccount);
+
+        let like = PlaylistLike::try_deserialize(&mut &like_info.try_borrow_data()?[..])?;
+        require!(like.playlist == playlist.key(), SpotifyError::PlaylistNotEmpty);
+        require!(
+            user_info.key() == like.user && user_info.is_writable,
+            SpotifyError::InvalidAccount
+        );
+        close_account(like_info, user_info)?;
+    }
+
+    for collaborator_info in collaborator_accounts {
+        require!(
+            collaborator_info.owner == &crate::ID && collaborator_info.is_writable,
+            SpotifyError::InvalidAccount
+        );
+
+        let collaborator = PlaylistCollaborator::try_deserialize(&mut &collaborator_info.try_borrow_data()?[..])?;
+        require!(collaborator.playlist == playlist.key(), SpotifyError::PlaylistNotEmpty);
+        close_account(collaborator_info, &authority_info)?;
+    }
+
+    close_search_entries(playlist.key(), playlist.search_entries_count, search_accounts, &authority_info)?;
+
+    // Likes closed with the playlist no longer count towards the owner
+    user_stats.total_likes_received = user_stats.total_likes_received.checked_sub(playlist.likes_count)
+        .ok_or(SpotifyError::ArithmeticUnderflow)?;
+
+    // Update user stats
+    user_stats.playlists_created = user_stats.playlists_created.checked_sub(1)
+        .ok_or(SpotifyError::ArithmeticUnderflow)?;
+    user_stats.last_active = clock.unix_timestamp;
+
+    emit!(PlaylistDeleted {
+        playlist: playlist.key(),
+        authority: playlist.authority,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Playlist deleted: {}", playlist.name);
+    Ok(())
+}
+
 pub fn add_track_to_playlist(
     ctx: Context<AddTrackToPlaylist>,
 ) -> Result<()> {
@@ -45,9 +244,21 @@ pub fn add_track_to_playlist(
     let clock = Clock::get()?;
 
     // Check if user has permission to add tracks
-    if playlist.authority != ctx.accounts.authority.key() && !playlist.is_collaborative {
-        return Err(SpotifyError::NoPermissionToAddTrack.into());
-    }
+    require!(
+        has_playlist_permission(
+            playlist,
+            ctx.accounts.authority.key(),
+            ctx.accounts.playlist_collaborator.as_deref(),
+            PlaylistCollaborator::PERMISSION_ADD_TRACKS,
+        ),
+        SpotifyError::NoPermissionToAddTrack
+    );
+    require_track_access(
+        &ctx.accounts.track,
+        ctx.accounts.authority.key(),
+        ctx.accounts.subscription.as_ref(),
+        clock.unix_timestamp,
+    )?;
 
     playlist_track.playlist = playlist.key();
     playlist_track.track = ctx.accounts.track.key();
@@ -58,8 +269,21 @@ pub fn add_track_to_playlist(
     // Increment tracks count in playlist
     playlist.tracks_count = playlist.tracks_count.checked_add(1)
         .ok_or(SpotifyError::ArithmeticOverflow)?;
+
+    // Increment playlists count on track
+    let track = &mut ctx.accounts.track;
+    track.playlists_count = track.playlists_count.checked_add(1)
+        .ok_or(SpotifyError::ArithmeticOverflow)?;
     playlist.updated_at = clock.unix_timestamp;
 
+    emit!(TrackAddedToPlaylist {
+        playlist: playlist.key(),
+        track: playlist_track.track,
+        added_by: playlist_track.added_by,
+        position: playlist_track.position,
+        timestamp: clock.unix_timestamp,
+    });
+
     msg!("Track added to playlist: {}", playlist.name);
     Ok(())
 }
@@ -69,7 +293,7 @@ pub fn add_collaborator(
     permissions: u8,
 ) -> Result<()> {
     let playlist_collaborator = &mut ctx.accounts.playlist_collaborator;
-    let playlist = &ctx.accounts.playlist;
+    let playlist = &mut ctx.accounts.playlist;
     let clock = Clock::get()?;
 
     // Validate permissions
@@ -83,10 +307,229 @@ pub fn add_collaborator(
     playlist_collaborator.permissions = permissions;
     playlist_collaborator.added_at = clock.unix_timestamp;
 
+    // Increment collaborators count in playlist
+    playlist.collaborators_count = playlist.collaborators_count.checked_add(1)
+        .ok_or(SpotifyError::ArithmeticOverflow)?;
+
+    emit!(CollaboratorAdded {
+        playlist: playlist.key(),
+        collaborator: playlist_collaborator.user,
+        permissions,
+        timestamp: clock.unix_timestamp,
+    });
+
     msg!("Collaborator added to playlist: {}", playlist.name);
     Ok(())
 }
 
+pub fn update_collaborator_permissions(
+    ctx: Context<UpdateCollaboratorPermissions>,
+    permissions: u8,
+) -> Result<()> {
+    let playlist_collaborator = &mut ctx.accounts.playlist_collaborator;
+    let clock = Clock::get()?;
+
+    require!(
+        permissions <= PlaylistCollaborator::PERMISSION_ALL,
+        SpotifyError::InvalidPermissions
+    );
+
+    playlist_collaborator.permissions = permissions;
+
+    emit!(CollaboratorPermissionsUpdated {
+        playlist: playlist_collaborator.playlist,
+        collaborator: playlist_collaborator.user,
+        permissions,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Collaborator permissions updated for playlist: {}", ctx.accounts.playlist.name);
+    Ok(())
+}
+
+pub fn remove_collaborator(
+    ctx: Context<RemoveCollaborator>,
+) -> Result<()> {
+    let playlist = &mut ctx.accounts.playlist;
+    let clock = Clock::get()?;
+
+    // Decrement collaborators count in playlist
+    playlist.collaborators_count = playlist.collaborators_count.checked_sub(1)
+        .ok_or(SpotifyError::ArithmeticUnderflow)?;
+
+    emit!(CollaboratorRemoved {
+        playlist: playlist.key(),
+        collaborator: ctx.accounts.playlist_collaborator.user,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Collaborator removed from playlist: {}", playlist.name);
+    Ok(())
+}
+
+pub fn remove_track_from_playlist(
+    ctx: Context<RemoveTrackFromPlaylist>,
+) -> Result<()> {
+    let playlist = &mut ctx.accounts.playlist;
+    let playlist_track = &ctx.accounts.playlist_track;
+    let clock = Clock::get()?;
+
+    require!(
+        has_playlist_permission(
+            playlist,
+            ctx.accounts.authority.key(),
+            ctx.accounts.playlist_collaborator.as_deref(),
+            PlaylistCollaborator::PERMISSION_REMOVE_TRACKS,
+        ),
+        SpotifyError::NoPermissionToRemoveTrack
+    );
+
+    // Close the gap left by the removed track
+    let start = playlist_track.position.checked_add(1)
+        .ok_or(SpotifyError::ArithmeticOverflow)?;
+    shift_playlist_tracks(playlist.key(), ctx.remaining_accounts, start, playlist.tracks_count, false)?;
+
+    // Decrement tracks count in playlist
+    playlist.tracks_count = playlist.tracks_count.checked_sub(1)
+        .ok_or(SpotifyError::ArithmeticUnderflow)?;
+    playlist.updated_at = clock.unix_timestamp;
+
+    // Decrement playlists count on track
+    let track = &mut ctx.accounts.track;
+    track.playlists_count = track.playlists_count.checked_sub(1)
+        .ok_or(SpotifyError::ArithmeticUnderflow)?;
+
+    emit!(TrackRemovedFromPlaylist {
+        playlist: playlist.key(),
+        track: playlist_track.track,
+        removed_by: ctx.accounts.authority.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Track removed from playlist: {}", playlist.name);
+    Ok(())
+}
+
+pub fn move_playlist_track(
+    ctx: Context<MovePlaylistTrack>,
+    new_position: u64,
+) -> Result<()> {
+    let playlist = &mut ctx.accounts.playlist;
+    let playlist_track = &mut ctx.accounts.playlist_track;
+    let clock = Clock::get()?;
+
+    require!(
+        has_playlist_permission(
+            playlist,
+            ctx.accounts.authority.key(),
+            ctx.accounts.playlist_collaborator.as_deref(),
+            PlaylistCollaborator::PERMISSION_EDIT_INFO,
+        ),
+        SpotifyError::NoPermissionToEditPlaylist
+    );
+
+    require!(new_position < playlist.tracks_count, SpotifyError::InvalidPlaylistPosition);
+
+    let old_position = playlist_track.position;
+    if new_position < old_position {
+        // Tracks between the new and old position shift one position later
+        shift_playlist_tracks(playlist.key(), ctx.remaining_accounts, new_position, old_position, true)?;
+    } else if new_position > old_position {
+        // Tracks between the old and new position shift one position earlier
+        shift_playlist_tracks(playlist.key(), ctx.remaining_accounts, old_position + 1, new_position + 1, false)?;
+    }
+
+    playlist_track.position = new_position;
+    playlist.updated_at = clock.unix_timestamp;
+
+    emit!(PlaylistTrackMoved {
+        playlist: playlist.key(),
+        track: playlist_track.track,
+        moved_by: ctx.accounts.authority.key(),
+        old_position,
+        new_position,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Track moved to position {} in playlist: {}", new_position, playlist.name);
+    Ok(())
+}
+
+/// Owners can always edit their playlist. Anyone else needs a `PlaylistCollaborator`
+/// entry on a collaborative playlist that grants `permission`.
+fn has_playlist_permission(
+    playlist: &Playlist,
+    user: Pubkey,
+    collaborator: Option<&PlaylistCollaborator>,
+    permission: u8,
+) -> bool {
+    if playlist.authority == user {
+        return true;
+    }
+
+    playlist.is_collaborative
+        && collaborator.is_some_and(|c| c.user == user && c.has_permission(permission))
+}
+
+/// Closes a program-owned account by hand, sending its rent to `destination`.
+pub(crate) fn close_account(info: &AccountInfo, destination: &AccountInfo) -> Result<()> {
+    let lamports = info.lamports();
+    **destination.try_borrow_mut_lamports()? = destination.lamports().checked_add(lamports)
+        .ok_or(SpotifyError::ArithmeticOverflow)?;
+    **info.try_borrow_mut_lamports()? = 0;
+
+    info.assign(&System::id());
+    info.realloc(0, false)?;
+    Ok(())
+}
+
+/// Moves every `PlaylistTrack` passed in `accounts` one position later (`increment`)
+/// or earlier. The accounts must belong to `playlist` and cover exactly the
+/// positions in `start..end`, so positions stay dense and unique.
+///
+/// Every entry is read and validated before any is written, so an account passed
+/// twice is seen at the same position both times and rejected.
+fn shift_playlist_tracks(
+    playlist: Pubkey,
+    accounts: &[AccountInfo],
+    start: u64,
+    end: u64,
+    increment: bool,
+) -> Result<()> {
+    let expected = end.saturating_sub(start);
+    require!(accounts.len() as u64 == expected, SpotifyError::PlaylistTracksMismatch);
+
+    let mut seen = vec![false; accounts.len()];
+    let mut entries = Vec::with_capacity(accounts.len());
+    for info in accounts {
+        require!(info.owner == &crate::ID && info.is_writable, SpotifyError::InvalidAccount);
+
+        let entry = PlaylistTrack::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(entry.playlist == playlist, SpotifyError::PlaylistTracksMismatch);
+        require!(
+            entry.position >= start && entry.position < end,
+            SpotifyError::PlaylistTracksMismatch
+        );
+
+        let index = (entry.position - start) as usize;
+        require!(!seen[index], SpotifyError::PlaylistTracksMismatch);
+        seen[index] = true;
+
+        entries.push(entry);
+    }
+
+    for (info, mut entry) in accounts.iter().zip(entries) {
+        entry.position = if increment {
+            entry.position.checked_add(1).ok_or(SpotifyError::ArithmeticOverflow)?
+        } else {
+            entry.position.checked_sub(1).ok_or(SpotifyError::ArithmeticUnderflow)?
+        };
+        entry.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    }
+
+    Ok(())
+}
+
 #[derive(Accounts)]
 #[instruction(name: String)]
 pub struct CreatePlaylist<'info> {
@@ -106,12 +549,62 @@ pub struct CreatePlaylist<'info> {
     )]
     pub user_stats: Account<'info, UserStats>,
 
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ActivityFeed::MAX_SIZE,
+        seeds = [b"activity_feed", authority.key().as_ref()],
+        bump
+    )]
+    pub activity_feed: Account<'info, ActivityFeed>,
+
     #[account(mut)]
     pub authority: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct UpdatePlaylist<'info> {
+    #[account(
+        mut,
+        seeds = [b"playlist", playlist.authority.as_ref(), playlist.name.as_bytes()],
+        bump
+    )]
+    pub playlist: Box<Account<'info, Playlist>>,
+
+    #[account(
+        seeds = [b"playlist_collaborator", playlist.key().as_ref(), authority.key().as_ref()],
+        bump,
+        has_one = playlist
+    )]
+    pub playlist_collaborator: Option<Account<'info, PlaylistCollaborator>>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DeletePlaylist<'info> {
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"playlist", playlist.authority.as_ref(), playlist.name.as_bytes()],
+        bump,
+        has_one = authority
+    )]
+    pub playlist: Box<Account<'info, Playlist>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", authority.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct AddTrackToPlaylist<'info> {
     #[account(
@@ -130,8 +623,22 @@ pub struct AddTrackToPlaylist<'info> {
     )]
     pub playlist_track: Account<'info, PlaylistTrack>,
 
-    /// CHECK: This is the track account we're adding to the playlist
-    pub track: AccountInfo<'info>,
+    #[account(mut)]
+    pub track: Box<Account<'info, Track>>,
+
+    #[account(
+        seeds = [b"playlist_collaborator", playlist.key().as_ref(), authority.key().as_ref()],
+        bump,
+        has_one = playlist
+    )]
+    pub playlist_collaborator: Option<Account<'info, PlaylistCollaborator>>,
+
+    // Only needed for premium tracks
+    #[account(
+        seeds = [b"subscription", authority.key().as_ref()],
+        bump
+    )]
+    pub subscription: Option<Account<'info, Subscription>>,
 
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -139,9 +646,76 @@ pub struct AddTrackToPlaylist<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct RemoveTrackFromPlaylist<'info> {
+    #[account(
+        mut,
+        seeds = [b"playlist", playlist.authority.as_ref(), playlist.name.as_bytes()],
+        bump
+    )]
+    pub playlist: Box<Account<'info, Playlist>>,
+
+    #[account(
+        mut,
+        close = added_by,
+        seeds = [b"playlist_track", playlist.key().as_ref(), track.key().as_ref()],
+        bump,
+        has_one = playlist,
+        has_one = added_by
+    )]
+    pub playlist_track: Account<'info, PlaylistTrack>,
+
+    #[account(mut)]
+    pub track: Box<Account<'info, Track>>,
+
+    /// CHECK: Receives the rent of the closed entry, checked against `playlist_track.added_by`
+    #[account(mut)]
+    pub added_by: AccountInfo<'info>,
+
+    #[account(
+        seeds = [b"playlist_collaborator", playlist.key().as_ref(), authority.key().as_ref()],
+        bump,
+        has_one = playlist
+    )]
+    pub playlist_collaborator: Option<Account<'info, PlaylistCollaborator>>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MovePlaylistTrack<'info> {
+    #[account(
+        mut,
+        seeds = [b"playlist", playlist.authority.as_ref(), playlist.name.as_bytes()],
+        bump
+    )]
+    pub playlist: Box<Account<'info, Playlist>>,
+
+    #[account(
+        mut,
+        seeds = [b"playlist_track", playlist.key().as_ref(), track.key().as_ref()],
+        bump,
+        has_one = playlist
+    )]
+    pub playlist_track: Account<'info, PlaylistTrack>,
+
+    /// CHECK: This is the track account being moved within the playlist
+    pub track: AccountInfo<'info>,
+
+    #[account(
+        seeds = [b"playlist_collaborator", playlist.key().as_ref(), authority.key().as_ref()],
+        bump,
+        has_one = playlist
+    )]
+    pub playlist_collaborator: Option<Account<'info, PlaylistCollaborator>>,
+
+    pub authority: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct AddCollaborator<'info> {
     #[account(
+        mut,
         seeds = [b"playlist", playlist.authority.as_ref(), playlist.name.as_bytes()],
         bump,
         has_one = authority
@@ -165,3 +739,46 @@ pub struct AddCollaborator<'info> {
 
     pub system_program: Program<'info, System>,
 }
+
+#[derive(Accounts)]
+pub struct UpdateCollaboratorPermissions<'info> {
+    #[account(
+        seeds = [b"playlist", playlist.authority.as_ref(), playlist.name.as_bytes()],
+        bump,
+        has_one = authority
+    )]
+    pub playlist: Box<Account<'info, Playlist>>,
+
+    #[account(
+        mut,
+        seeds = [b"playlist_collaborator", playlist.key().as_ref(), playlist_collaborator.user.as_ref()],
+        bump,
+        has_one = playlist
+    )]
+    pub playlist_collaborator: Account<'info, PlaylistCollaborator>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveCollaborator<'info> {
+    #[account(
+        mut,
+        seeds = [b"playlist", playlist.authority.as_ref(), playlist.name.as_bytes()],
+        bump,
+        has_one = authority
+    )]
+    pub playlist: Box<Account<'info, Playlist>>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"playlist_collaborator", playlist.key().as_ref(), playlist_collaborator.user.as_ref()],
+        bump,
+        has_one = playlist
+    )]
+    pub playlist_collaborator: Account<'info, PlaylistCollaborator>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
@@ -1,8 +1,11 @@
 use anchor_lang::prelude::*;
-use crate::state::*; // This will now include SearchIndex
+use crate::state::*;
 use crate::errors::SpotifyError;
+use crate::events::*;
+use crate::instructions::playlist::close_account;
+use anchor_lang::system_program;
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
 pub struct SearchFilters {
     pub genre: Option<String>,
     pub min_duration: Option<u64>,
@@ -11,7 +14,7 @@ pub struct SearchFilters {
     pub created_after: Option<i64>,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
 pub struct SearchResult {
     pub result_type: u8, // 1 = track, 2 = playlist, 3 = user
     pub pubkey: Pubkey,
@@ -28,23 +31,79 @@ impl SearchResult {
 }
 
 #[derive(Accounts)]
-#[instruction(search_term: String, target_type: u8)]
+#[instruction(search_term: String, target_type: u8, target_pubkey: Pubkey)]
 pub struct CreateSearchIndex<'info> {
     #[account(
         init,
         payer = authority,
         space = 8 + SearchIndex::MAX_SIZE,
-        seeds = [b"search_index", search_term.to_lowercase().as_bytes(), &target_type.to_le_bytes()],
+        seeds = [b"search_index", SearchIndex::normalize(&search_term).as_bytes(), target_pubkey.as_ref()],
         bump
     )]
     pub search_index: Account<'info, SearchIndex>,
 
+    /// CHECK: Deserialized in the handler according to `target_type`
+    #[account(mut, address = target_pubkey)]
+    pub target_account: UncheckedAccount<'info>,
+
     #[account(mut)]
     pub authority: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct RemoveSearchIndex<'info> {
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"search_index", search_index.search_term.as_bytes(), search_index.target_pubkey.as_ref()],
+        bump,
+        constraint = search_index.creator == authority.key() @ SpotifyError::Unauthorized
+    )]
+    pub search_index: Account<'info, SearchIndex>,
+
+    /// CHECK: Deserialized in the handler according to `search_index.target_type`
+    #[account(mut, address = search_index.target_pubkey)]
+    pub target_account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(new_term: String)]
+pub struct UpdateSearchIndex<'info> {
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"search_index", old_search_index.search_term.as_bytes(), old_search_index.target_pubkey.as_ref()],
+        bump,
+        constraint = old_search_index.creator == authority.key() @ SpotifyError::Unauthorized
+    )]
+    pub old_search_index: Account<'info, SearchIndex>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + SearchIndex::MAX_SIZE,
+        seeds = [b"search_index", SearchIndex::normalize(&new_term).as_bytes(), old_search_index.target_pubkey.as_ref()],
+        bump
+    )]
+    pub new_search_index: Account<'info, SearchIndex>,
+
+    /// CHECK: Deserialized in the handler according to `old_search_index.target_type`
+    #[account(address = old_search_index.target_pubkey)]
+    pub target_account: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Indexes the target under one term. The term must be one of the target's
+/// `SearchIndex::tokenize` tokens, so each target has at most `MAX_TOKENS` entries.
 pub fn create_search_index(
     ctx: Context<CreateSearchIndex>,
     search_term: String,
@@ -52,16 +111,309 @@ pub fn create_search_index(
     target_pubkey: Pubkey,
 ) -> Result<()> {
     let search_index = &mut ctx.accounts.search_index;
+    let target_info = ctx.accounts.target_account.to_account_info();
     let clock = Clock::get()?;
 
-    require!(search_term.len() <= 64, SpotifyError::SearchTermTooLong);
-    require!(search_term.len() > 0, SpotifyError::SearchTermEmpty);
+    require!(search_term.len() <= SearchIndex::MAX_TERM_LEN, SpotifyError::SearchTermTooLong);
+    require!(!search_term.is_empty(), SpotifyError::SearchTermEmpty);
+
+    // Only the creator of the target may index it
+    let (creator, terms) = target_terms(target_type, &target_info)?;
+    require_keys_eq!(creator, ctx.accounts.authority.key(), SpotifyError::Unauthorized);
+
+    let search_term = SearchIndex::normalize(&search_term);
+    require!(terms.contains(&search_term), SpotifyError::SearchTermNotInTarget);
 
-    search_index.search_term = search_term.to_lowercase();
+    search_index.search_term = search_term.clone();
     search_index.target_type = target_type;
     search_index.target_pubkey = target_pubkey;
+    search_index.creator = creator;
     search_index.created_at = clock.unix_timestamp;
 
-    msg!("Search index created for: {}", search_index.search_term);
+    update_search_entries_count(target_type, &target_info, true)?;
+
+    emit!(SearchIndexCreated {
+        search_index: search_index.key(),
+        search_term: search_term.clone(),
+        target_type,
+        target_pubkey,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Search index created for: {}", search_term);
     Ok(())
 }
+
+/// Closes the entry and refunds its rent to the creator who paid for it.
+pub fn remove_search_index(
+    ctx: Context<RemoveSearchIndex>,
+) -> Result<()> {
+    let search_index = &ctx.accounts.search_index;
+    let clock = Clock::get()?;
+
+    update_search_entries_count(
+        search_index.target_type,
+        &ctx.accounts.target_account.to_account_info(),
+        false,
+    )?;
+
+    emit!(SearchIndexRemoved {
+        search_index: search_index.key(),
+        search_term: search_index.search_term.clone(),
+        target_pubkey: search_index.target_pubkey,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Search index entry removed from: {}", search_index.search_term);
+    Ok(())
+}
+
+/// Moves an entry to another of the target's current terms, for example after a
+/// username or display name change.
+pub fn update_search_index(
+    ctx: Context<UpdateSearchIndex>,
+    new_term: String,
+) -> Result<()> {
+    let old_search_index = &ctx.accounts.old_search_index;
+    let new_search_index = &mut ctx.accounts.new_search_index;
+    let clock = Clock::get()?;
+
+    require!(new_term.len() <= SearchIndex::MAX_TERM_LEN, SpotifyError::SearchTermTooLong);
+    require!(!new_term.is_empty(), SpotifyError::SearchTermEmpty);
+
+    let new_term = SearchIndex::normalize(&new_term);
+    let (_, terms) = target_terms(old_search_index.target_type, &ctx.accounts.target_account)?;
+    require!(terms.contains(&new_term), SpotifyError::SearchTermNotInTarget);
+
+    new_search_index.search_term = new_term.clone();
+    new_search_index.target_type = old_search_index.target_type;
+    new_search_index.target_pubkey = old_search_index.target_pubkey;
+    new_search_index.creator = old_search_index.creator;
+    new_search_index.created_at = clock.unix_timestamp;
+
+    emit!(SearchIndexUpdated {
+        old_search_index: old_search_index.key(),
+        new_search_index: new_search_index.key(),
+        old_term: old_search_index.search_term.clone(),
+        new_term: new_term.clone(),
+        target_pubkey: new_search_index.target_pubkey,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Search index entry moved from {} to {}", old_search_index.search_term, new_term);
+    Ok(())
+}
+
+// Helper functions
+
+/// Wallet that created the `target_type` account in `info`, and the terms it can be
+/// indexed under. Private playlists have no terms.
+fn target_terms(target_type: u8, info: &AccountInfo) -> Result<(Pubkey, Vec<String>)> {
+    require!(info.owner == &crate::ID, SpotifyError::InvalidAccount);
+    let data = info.try_borrow_data()?;
+
+    match target_type {
+        SearchIndex::TARGET_TRACK => {
+            let track = Track::try_deserialize(&mut &data[..])?;
+            Ok((track.created_by, SearchIndex::tokenize(&[&track.title, &track.artist])))
+        }
+        SearchIndex::TARGET_PLAYLIST => {
+            let playlist = Playlist::try_deserialize(&mut &data[..])?;
+            let terms = if playlist.is_public { SearchIndex::tokenize(&[&playlist.name]) } else { Vec::new() };
+            Ok((playlist.authority, terms))
+        }
+        SearchIndex::TARGET_USER => {
+            let profile = UserProfile::try_deserialize(&mut &data[..])?;
+            Ok((profile.authority, SearchIndex::tokenize(&[&profile.username, &profile.display_name])))
+        }
+        _ => err!(SpotifyError::InvalidSearchTargetType),
+    }
+}
+
+/// Indexes a newly created target from its create instruction. `accounts` are the
+/// `SearchIndex` PDAs of the `terms` the caller wants indexed, in `terms` order, so
+/// clients can leave terms out to stay within the transaction size. Returns the
+/// number of entries created.
+pub(crate) fn index_new_target<'info>(
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+    terms: &[String],
+    target_type: u8,
+    target_pubkey: Pubkey,
+    now: i64,
+) -> Result<u64> {
+    let mut terms = terms.iter();
+
+    for info in accounts {
+        // Skip the terms the caller left out
+        let (term, bump) = loop {
+            let term = terms.next().ok_or(SpotifyError::SearchIndexMismatch)?;
+            let (address, bump) = Pubkey::find_program_address(
+                &[b"search_index", term.as_bytes(), target_pubkey.as_ref()],
+                &crate::ID,
+            );
+            if address == info.key() {
+                break (term, bump);
+            }
+        };
+
+        create_pda_account(
+            payer,
+            system_program,
+            info,
+            8 + SearchIndex::MAX_SIZE,
+            &[b"search_index", term.as_bytes(), target_pubkey.as_ref(), &[bump]],
+        )?;
+
+        let entry = SearchIndex {
+            search_term: term.clone(),
+            target_type,
+            target_pubkey,
+            creator: payer.key(),
+            created_at: now,
+        };
+        entry.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        emit!(SearchIndexCreated {
+            search_index: info.key(),
+            search_term: term.clone(),
+            target_type,
+            target_pubkey,
+            timestamp: now,
+        });
+    }
+
+    Ok(accounts.len() as u64)
+}
+
+/// Closes every `SearchIndex` entry of `target` found in `accounts`, refunding the rent to
+/// `creator`. Exactly `count` entries must be passed, so a deleted target leaves none behind.
+pub(crate) fn close_search_entries<'info>(
+    target: Pubkey,
+    count: u64,
+    accounts: &[AccountInfo<'info>],
+    creator: &AccountInfo<'info>,
+) -> Result<()> {
+    require!(accounts.len() as u64 == count, SpotifyError::SearchIndexMismatch);
+
+    // Closed accounts are no longer program-owned, so passing one twice fails the owner check
+    for info in accounts {
+        require!(info.owner == &crate::ID && info.is_writable, SpotifyError::InvalidAccount);
+
+        let entry = SearchIndex::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(entry.target_pubkey == target, SpotifyError::SearchIndexMismatch);
+        require_keys_eq!(entry.creator, creator.key(), SpotifyError::Unauthorized);
+        close_account(info, creator)?;
+    }
+    Ok(())
+}
+
+/// Closes the `SearchIndex` entries of `target` whose term is no longer one of `terms`,
+/// after its searchable text changed. `accounts` must hold all `count` entries, so none
+/// can be left behind. Returns the number of entries kept.
+pub(crate) fn prune_search_entries(
+    target: Pubkey,
+    count: u64,
+    accounts: &[AccountInfo],
+    terms: &[String],
+    creator: &AccountInfo,
+) -> Result<u64> {
+    require!(accounts.len() as u64 == count, SpotifyError::SearchIndexMismatch);
+
+    let mut kept = count;
+    for (i, info) in accounts.iter().enumerate() {
+        // Kept entries stay program-owned, so duplicates are checked explicitly
+        require!(
+            !accounts[..i].iter().any(|other| other.key() == info.key()),
+            SpotifyError::SearchIndexMismatch
+        );
+        require!(info.owner == &crate::ID && info.is_writable, SpotifyError::InvalidAccount);
+
+        let entry = SearchIndex::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(entry.target_pubkey == target, SpotifyError::SearchIndexMismatch);
+        require_keys_eq!(entry.creator, creator.key(), SpotifyError::Unauthorized);
+
+        if !terms.contains(&entry.search_term) {
+            close_account(info, creator)?;
+            kept = kept.checked_sub(1).ok_or(SpotifyError::ArithmeticUnderflow)?;
+        }
+    }
+    Ok(kept)
+}
+
+/// Counts an entry added to (`increment`) or removed from the target's `search_entries_count`.
+fn update_search_entries_count(target_type: u8, info: &AccountInfo, increment: bool) -> Result<()> {
+    match target_type {
+        SearchIndex::TARGET_TRACK => update_count::<Track>(info, |track| &mut track.search_entries_count, increment),
+        SearchIndex::TARGET_PLAYLIST => update_count::<Playlist>(info, |playlist| &mut playlist.search_entries_count, increment),
+        SearchIndex::TARGET_USER => update_count::<UserProfile>(info, |profile| &mut profile.search_entries_count, increment),
+        _ => err!(SpotifyError::InvalidSearchTargetType),
+    }
+}
+
+fn update_count<T: AccountSerialize + AccountDeserialize>(
+    info: &AccountInfo,
+    field: impl FnOnce(&mut T) -> &mut u64,
+    increment: bool,
+) -> Result<()> {
+    let mut account = T::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    let count = field(&mut account);
+    *count = if increment {
+        count.checked_add(1).ok_or(SpotifyError::ArithmeticOverflow)?
+    } else {
+        count.checked_sub(1).ok_or(SpotifyError::ArithmeticUnderflow)?
+    };
+    account.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+}
+
+/// Creates a program-owned PDA, also when someone already sent lamports to its address.
+pub(crate) fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    info: &AccountInfo<'info>,
+    space: usize,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let current_lamports = info.lamports();
+
+    if current_lamports == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::CreateAccount { from: payer.clone(), to: info.clone() },
+                &[seeds],
+            ),
+            rent,
+            space as u64,
+            &crate::ID,
+        );
+    }
+
+    if rent > current_lamports {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer { from: payer.clone(), to: info.clone() },
+            ),
+            rent - current_lamports,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Allocate { account_to_allocate: info.clone() },
+            &[seeds],
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Assign { account_to_assign: info.clone() },
+            &[seeds],
+        ),
+        &crate::ID,
+    )
+}
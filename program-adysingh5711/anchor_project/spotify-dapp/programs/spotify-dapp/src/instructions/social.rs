@@ -1,6 +1,10 @@
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
 use crate::state::*;
 use crate::errors::SpotifyError;
+use crate::events::*;
+use crate::instructions::playlist::close_account;
+use anchor_lang::system_program;
 
 #[derive(Accounts)]
 pub struct LikeTrack<'info> {
@@ -45,6 +49,141 @@ pub struct LikeTrack<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct UnlikeTrack<'info> {
+    #[account(mut)]
+    pub track: Account<'info, Track>,
+
+    /// CHECK: Loaded in the handler so a missing like returns `TrackNotLiked`
+    #[account(
+        mut,
+        seeds = [b"track_like", user.key().as_ref(), track.key().as_ref()],
+        bump
+    )]
+    pub track_like: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", track.created_by.as_ref()],
+        bump
+    )]
+    pub creator_stats: Account<'info, UserStats>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + ActivityFeed::MAX_SIZE,
+        seeds = [b"activity_feed", user.key().as_ref()],
+        bump
+    )]
+    pub activity_feed: Account<'info, ActivityFeed>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LikePlaylist<'info> {
+    #[account(
+        mut,
+        seeds = [b"playlist", playlist.authority.as_ref(), playlist.name.as_bytes()],
+        bump
+    )]
+    pub playlist: Box<Account<'info, Playlist>>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + PlaylistLike::MAX_SIZE,
+        seeds = [b"playlist_like", user.key().as_ref(), playlist.key().as_ref()],
+        bump
+    )]
+    pub playlist_like: Account<'info, PlaylistLike>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", playlist.authority.as_ref()],
+        bump
+    )]
+    pub owner_stats: Account<'info, UserStats>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + ActivityFeed::MAX_SIZE,
+        seeds = [b"activity_feed", user.key().as_ref()],
+        bump
+    )]
+    pub activity_feed: Account<'info, ActivityFeed>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnlikePlaylist<'info> {
+    #[account(
+        mut,
+        seeds = [b"playlist", playlist.authority.as_ref(), playlist.name.as_bytes()],
+        bump
+    )]
+    pub playlist: Box<Account<'info, Playlist>>,
+
+    /// CHECK: Loaded in the handler so a missing like returns `PlaylistNotLiked`
+    #[account(
+        mut,
+        seeds = [b"playlist_like", user.key().as_ref(), playlist.key().as_ref()],
+        bump
+    )]
+    pub playlist_like: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", playlist.authority.as_ref()],
+        bump
+    )]
+    pub owner_stats: Account<'info, UserStats>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + ActivityFeed::MAX_SIZE,
+        seeds = [b"activity_feed", user.key().as_ref()],
+        bump
+    )]
+    pub activity_feed: Account<'info, ActivityFeed>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct FollowUser<'info> {
     #[account(
@@ -85,6 +224,77 @@ pub struct FollowUser<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct UnfollowUser<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_profile", follower.key().as_ref()],
+        bump
+    )]
+    pub follower_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"user_profile", following_profile.authority.as_ref()],
+        bump
+    )]
+    pub following_profile: Account<'info, UserProfile>,
+
+    /// CHECK: Loaded in the handler so a missing follow returns `NotFollowing`
+    #[account(
+        mut,
+        seeds = [b"user_follow", follower.key().as_ref(), following_profile.key().as_ref()],
+        bump
+    )]
+    pub user_follow: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub follower: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveOrphanedFollow<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_profile", follower.key().as_ref()],
+        bump
+    )]
+    pub follower_profile: Account<'info, UserProfile>,
+
+    /// CHECK: The followed profile, which may have been closed or re-created
+    pub following_profile: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = follower,
+        seeds = [b"user_follow", follower.key().as_ref(), following_profile.key().as_ref()],
+        bump,
+        constraint = user_follow.follower == follower_profile.key() @ SpotifyError::NotFollowing,
+        constraint = user_follow.following == following_profile.key() @ SpotifyError::NotFollowing
+    )]
+    pub user_follow: Account<'info, UserFollow>,
+
+    #[account(mut)]
+    pub follower: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateActivityFeed<'info> {
+    /// CHECK: Feed in the pre-ring-buffer layout, which `Account` cannot deserialize
+    #[account(
+        mut,
+        seeds = [b"activity_feed", user.key().as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub activity_feed: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
 pub fn like_track(
     ctx: Context<LikeTrack>,
 ) -> Result<()> {
@@ -110,26 +320,173 @@ pub fn like_track(
     // Update user activity
     user_stats.last_active = clock.unix_timestamp;
 
-    // Create or update activity feed entry
-    if activity_feed.user == Pubkey::default() {
-        // Initialize new activity feed
-        activity_feed.user = ctx.accounts.user.key();
-        activity_feed.activity_type = ActivityFeed::ACTIVITY_TRACK_LIKED;
-        activity_feed.target = track.key();
-        activity_feed.metadata = format!("Liked track: {}", track.title);
-        activity_feed.created_at = clock.unix_timestamp;
-    } else {
-        // Update existing activity feed with latest action
-        activity_feed.activity_type = ActivityFeed::ACTIVITY_TRACK_LIKED;
-        activity_feed.target = track.key();
-        activity_feed.metadata = format!("Liked track: {}", track.title);
-        activity_feed.created_at = clock.unix_timestamp;
-    }
+    // Append to activity feed
+    activity_feed.record(
+        ctx.accounts.user.key(),
+        ActivityFeed::ACTIVITY_TRACK_LIKED,
+        track.key(),
+        format!("Liked track: {}", track.title),
+        clock.unix_timestamp,
+    );
+
+    emit!(TrackLiked {
+        track: track.key(),
+        user: ctx.accounts.user.key(),
+        likes_count: track.likes_count,
+        timestamp: clock.unix_timestamp,
+    });
 
     msg!("Track liked: {}", track.title);
     Ok(())
 }
 
+pub fn unlike_track(
+    ctx: Context<UnlikeTrack>,
+) -> Result<()> {
+    let track = &mut ctx.accounts.track;
+    let user_stats = &mut ctx.accounts.user_stats;
+    let creator_stats = &mut ctx.accounts.creator_stats;
+    let activity_feed = &mut ctx.accounts.activity_feed;
+    let clock = Clock::get()?;
+
+    let track_like_info = ctx.accounts.track_like.to_account_info();
+    let track_like: TrackLike = load_relationship(&track_like_info, SpotifyError::TrackNotLiked)?;
+    require!(
+        track_like.user == ctx.accounts.user.key() && track_like.track == track.key(),
+        SpotifyError::TrackNotLiked
+    );
+    close_account(&track_like_info, &ctx.accounts.user.to_account_info())?;
+
+    // Decrement likes count on track
+    track.likes_count = track.likes_count.checked_sub(1)
+        .ok_or(SpotifyError::ArithmeticUnderflow)?;
+
+    // Update creator stats
+    creator_stats.total_likes_received = creator_stats.total_likes_received.checked_sub(1)
+        .ok_or(SpotifyError::ArithmeticUnderflow)?;
+
+    // Update user activity
+    user_stats.last_active = clock.unix_timestamp;
+
+    // Append to activity feed
+    activity_feed.record(
+        ctx.accounts.user.key(),
+        ActivityFeed::ACTIVITY_TRACK_UNLIKED,
+        track.key(),
+        format!("Unliked track: {}", track.title),
+        clock.unix_timestamp,
+    );
+
+    emit!(TrackUnliked {
+        track: track.key(),
+        user: ctx.accounts.user.key(),
+        likes_count: track.likes_count,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Track unliked: {}", track.title);
+    Ok(())
+}
+
+pub fn like_playlist(
+    ctx: Context<LikePlaylist>,
+) -> Result<()> {
+    let playlist = &mut ctx.accounts.playlist;
+    let playlist_like = &mut ctx.accounts.playlist_like;
+    let user_stats = &mut ctx.accounts.user_stats;
+    let owner_stats = &mut ctx.accounts.owner_stats;
+    let activity_feed = &mut ctx.accounts.activity_feed;
+    let clock = Clock::get()?;
+
+    // Private playlists can only be liked by their owner
+    require!(
+        playlist.is_public || playlist.authority == ctx.accounts.user.key(),
+        SpotifyError::PlaylistNotVisible
+    );
+
+    playlist_like.user = ctx.accounts.user.key();
+    playlist_like.playlist = playlist.key();
+    playlist_like.created_at = clock.unix_timestamp;
+
+    // Increment likes count on playlist
+    playlist.likes_count = playlist.likes_count.checked_add(1)
+        .ok_or(SpotifyError::ArithmeticOverflow)?;
+
+    // Update owner stats
+    owner_stats.total_likes_received = owner_stats.total_likes_received.checked_add(1)
+        .ok_or(SpotifyError::ArithmeticOverflow)?;
+
+    // Update user activity
+    user_stats.last_active = clock.unix_timestamp;
+
+    // Append to activity feed
+    activity_feed.record(
+        ctx.accounts.user.key(),
+        ActivityFeed::ACTIVITY_PLAYLIST_LIKED,
+        playlist.key(),
+        format!("Liked playlist: {}", playlist.name),
+        clock.unix_timestamp,
+    );
+
+    emit!(PlaylistLiked {
+        playlist: playlist.key(),
+        user: ctx.accounts.user.key(),
+        likes_count: playlist.likes_count,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Playlist liked: {}", playlist.name);
+    Ok(())
+}
+
+pub fn unlike_playlist(
+    ctx: Context<UnlikePlaylist>,
+) -> Result<()> {
+    let playlist = &mut ctx.accounts.playlist;
+    let user_stats = &mut ctx.accounts.user_stats;
+    let owner_stats = &mut ctx.accounts.owner_stats;
+    let activity_feed = &mut ctx.accounts.activity_feed;
+    let clock = Clock::get()?;
+
+    let playlist_like_info = ctx.accounts.playlist_like.to_account_info();
+    let playlist_like: PlaylistLike = load_relationship(&playlist_like_info, SpotifyError::PlaylistNotLiked)?;
+    require!(
+        playlist_like.user == ctx.accounts.user.key() && playlist_like.playlist == playlist.key(),
+        SpotifyError::PlaylistNotLiked
+    );
+    close_account(&playlist_like_info, &ctx.accounts.user.to_account_info())?;
+
+    // Decrement likes count on playlist
+    playlist.likes_count = playlist.likes_count.checked_sub(1)
+        .ok_or(SpotifyError::ArithmeticUnderflow)?;
+
+    // Update owner stats
+    owner_stats.total_likes_received = owner_stats.total_likes_received.checked_sub(1)
+        .ok_or(SpotifyError::ArithmeticUnderflow)?;
+
+    // Update user activity
+    user_stats.last_active = clock.unix_timestamp;
+
+    // Append to activity feed
+    activity_feed.record(
+        ctx.accounts.user.key(),
+        ActivityFeed::ACTIVITY_PLAYLIST_UNLIKED,
+        playlist.key(),
+        format!("Unliked playlist: {}", playlist.name),
+        clock.unix_timestamp,
+    );
+
+    emit!(PlaylistUnliked {
+        playlist: playlist.key(),
+        user: ctx.accounts.user.key(),
+        likes_count: playlist.likes_count,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Playlist unliked: {}", playlist.name);
+    Ok(())
+}
+
 pub fn follow_user(
     ctx: Context<FollowUser>,
 ) -> Result<()> {
@@ -157,22 +514,174 @@ pub fn follow_user(
     follower_profile.following_count = follower_profile.following_count.checked_add(1)
         .ok_or(SpotifyError::ArithmeticOverflow)?;
 
-    // Create or update activity feed entry
-    if activity_feed.user == Pubkey::default() {
-        // Initialize new activity feed
-        activity_feed.user = ctx.accounts.follower.key();
-        activity_feed.activity_type = ActivityFeed::ACTIVITY_USER_FOLLOWED;
-        activity_feed.target = following_profile.key();
-        activity_feed.metadata = format!("Followed {}", following_profile.username);
-        activity_feed.created_at = clock.unix_timestamp;
+    // Append to activity feed
+    activity_feed.record(
+        ctx.accounts.follower.key(),
+        ActivityFeed::ACTIVITY_USER_FOLLOWED,
+        following_profile.key(),
+        format!("Followed {}", following_profile.username),
+        clock.unix_timestamp,
+    );
+
+    emit!(UserFollowed {
+        follower: follower_profile.key(),
+        following: following_profile.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("User {} followed {}", follower_profile.username, following_profile.username);
+    Ok(())
+}
+
+pub fn unfollow_user(
+    ctx: Context<UnfollowUser>,
+) -> Result<()> {
+    let follower_profile = &mut ctx.accounts.follower_profile;
+    let following_profile = &mut ctx.accounts.following_profile;
+    let clock = Clock::get()?;
+
+    let user_follow_info = ctx.accounts.user_follow.to_account_info();
+    let user_follow: UserFollow = load_relationship(&user_follow_info, SpotifyError::NotFollowing)?;
+    require!(
+        user_follow.follower == follower_profile.key() && user_follow.following == following_profile.key(),
+        SpotifyError::NotFollowing
+    );
+
+    // A profile re-created after the follow did not gain this follower
+    require!(
+        following_profile.created_at <= user_follow.created_at,
+        SpotifyError::FollowOrphaned
+    );
+    close_account(&user_follow_info, &ctx.accounts.follower.to_account_info())?;
+
+    // Update follower count for unfollowed user
+    following_profile.followers_count = following_profile.followers_count.checked_sub(1)
+        .ok_or(SpotifyError::ArithmeticUnderflow)?;
+
+    // Update following count for follower user
+    follower_profile.following_count = follower_profile.following_count.checked_sub(1)
+        .ok_or(SpotifyError::ArithmeticUnderflow)?;
+
+    emit!(UserUnfollowed {
+        follower: follower_profile.key(),
+        following: following_profile.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("User {} unfollowed {}", follower_profile.username, following_profile.username);
+    Ok(())
+}
+
+/// Closes a follow record whose target profile was closed, or closed and re-created
+/// after the follow happened, and gives the follower back the rent.
+pub fn remove_orphaned_follow(
+    ctx: Context<RemoveOrphanedFollow>,
+) -> Result<()> {
+    let follower_profile = &mut ctx.accounts.follower_profile;
+    let following_info = ctx.accounts.following_profile.to_account_info();
+    let clock = Clock::get()?;
+
+    let orphaned = if following_info.owner != &crate::ID || following_info.data_is_empty() {
+        true
     } else {
-        // Update existing activity feed with latest action
-        activity_feed.activity_type = ActivityFeed::ACTIVITY_USER_FOLLOWED;
-        activity_feed.target = following_profile.key();
-        activity_feed.metadata = format!("Followed {}", following_profile.username);
-        activity_feed.created_at = clock.unix_timestamp;
+        let following_profile = UserProfile::try_deserialize(&mut &following_info.try_borrow_data()?[..])?;
+        following_profile.created_at > ctx.accounts.user_follow.created_at
+    };
+    require!(orphaned, SpotifyError::FollowNotOrphaned);
+
+    // Update following count for follower user
+    follower_profile.following_count = follower_profile.following_count.checked_sub(1)
+        .ok_or(SpotifyError::ArithmeticUnderflow)?;
+
+    emit!(OrphanedFollowRemoved {
+        follower: follower_profile.key(),
+        following: following_info.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Orphaned follow removed for {}", follower_profile.username);
+    Ok(())
+}
+
+/// Converts a feed created before the ring buffer into the current layout, keeping its
+/// single entry. The user pays for the extra space.
+pub fn migrate_activity_feed(
+    ctx: Context<MigrateActivityFeed>,
+) -> Result<()> {
+    let feed_info = ctx.accounts.activity_feed.to_account_info();
+    let clock = Clock::get()?;
+
+    require!(
+        feed_info.data_len() == LegacyActivityFeed::ACCOUNT_LEN,
+        SpotifyError::ActivityFeedAlreadyMigrated
+    );
+
+    let legacy = {
+        let data = feed_info.try_borrow_data()?;
+        require!(data.starts_with(ActivityFeed::DISCRIMINATOR), SpotifyError::InvalidAccount);
+        LegacyActivityFeed::deserialize(&mut &data[8..])?
+    };
+
+    let mut activity_feed = ActivityFeed {
+        user: ctx.accounts.user.key(),
+        head: 0,
+        entries: Vec::with_capacity(1),
+    };
+    if legacy.created_at != 0 {
+        activity_feed.record(
+            legacy.user,
+            legacy.activity_type,
+            legacy.target,
+            legacy.metadata,
+            legacy.created_at,
+        );
     }
 
-    msg!("User {} followed {}", follower_profile.username, following_profile.username);
+    resize_account(
+        &ctx.accounts.user.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &feed_info,
+        8 + ActivityFeed::MAX_SIZE,
+    )?;
+    activity_feed.try_serialize(&mut &mut feed_info.try_borrow_mut_data()?[..])?;
+
+    emit!(ActivityFeedMigrated {
+        user: activity_feed.user,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Activity feed migrated");
+    Ok(())
+}
+
+/// Deserializes a like or follow record, returning `missing` when it was never created.
+fn load_relationship<T: AccountDeserialize>(info: &AccountInfo, missing: SpotifyError) -> Result<T> {
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Err(missing.into());
+    }
+    T::try_deserialize(&mut &info.try_borrow_data()?[..])
+}
+
+/// Grows or shrinks a program-owned account, topping up rent from `payer` when needed.
+fn resize_account<'info>(
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    info: &AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(new_len);
+    let current_lamports = info.lamports();
+
+    if rent > current_lamports {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer { from: payer.clone(), to: info.clone() },
+            ),
+            rent - current_lamports,
+        )?;
+    }
+
+    info.realloc(new_len, false)?;
     Ok(())
 }
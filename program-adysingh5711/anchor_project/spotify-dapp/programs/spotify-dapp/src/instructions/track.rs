@@ -1,9 +1,17 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::errors::SpotifyError;
+use crate::events::*;
+use crate::instructions::search::{close_search_entries, index_new_target};
+use crate::instructions::playlist::close_account;
+use crate::instructions::payments::{collect_play_payment, require_track_access};
+use crate::instructions::album::append_album_track;
 
-pub fn create_track(
-    ctx: Context<CreateTrack>,
+/// Remaining accounts are the `SearchIndex` PDAs of the title and artist terms to index,
+/// in `SearchIndex::tokenize` order.
+#[allow(clippy::too_many_arguments)]
+pub fn create_track<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateTrack<'info>>,
     title: String,
     artist: String,
     album: String,
@@ -11,19 +19,26 @@ pub fn create_track(
     duration: u64,
     audio_url: String,
     cover_image: String,
+    price_lamports: u64,
+    is_premium: bool,
 ) -> Result<()> {
     let track = &mut ctx.accounts.track;
     let user_stats = &mut ctx.accounts.user_stats;
+    let activity_feed = &mut ctx.accounts.activity_feed;
     let clock = Clock::get()?;
 
     require!(title.len() <= 128, SpotifyError::TrackTitleTooLong);
-    require!(title.len() > 0, SpotifyError::TrackTitleEmpty);
+    require!(!title.is_empty(), SpotifyError::TrackTitleEmpty);
     require!(artist.len() <= 64, SpotifyError::ArtistNameTooLong);
     require!(album.len() <= 64, SpotifyError::AlbumNameTooLong);
     require!(genre.len() <= 32, SpotifyError::GenreTooLong);
     require!(audio_url.len() <= 256, SpotifyError::AudioUrlTooLong);
     require!(cover_image.len() <= 256, SpotifyError::CoverImageUrlTooLong);
     require!(duration > 0, SpotifyError::InvalidDuration);
+    require!(
+        price_lamports == 0 || ctx.accounts.creator_escrow.is_some(),
+        SpotifyError::CreatorEscrowMissing
+    );
 
     track.title = title;
     track.artist = artist;
@@ -34,18 +49,227 @@ pub fn create_track(
     track.cover_image = cover_image;
     track.likes_count = 0;
     track.plays_count = 0;
+    track.playlists_count = 0;
+    track.listeners_count = 0;
+    track.tips_received = 0;
+    track.price_lamports = price_lamports;
+    track.is_premium = is_premium;
+    track.album_key = None;
+    track.artist_profile = None;
+
+    // Link to the creator's artist profile, whose name must match
+    if let Some(artist_profile) = ctx.accounts.artist_profile.as_ref() {
+        require!(track.artist == artist_profile.name, SpotifyError::ArtistNameMismatch);
+        track.artist_profile = Some(artist_profile.key());
+    }
+
+    // A verified artist's name can only be used through their artist profile
+    let claim_info = ctx.accounts.artist_name_claim.to_account_info();
+    if claim_info.owner == &crate::ID && !claim_info.data_is_empty() {
+        let claim = ArtistNameClaim::try_deserialize(&mut &claim_info.try_borrow_data()?[..])?;
+        require!(
+            track.artist_profile == Some(claim.artist_profile),
+            SpotifyError::ArtistNameClaimed
+        );
+    }
+
     track.created_by = ctx.accounts.authority.key();
     track.created_at = clock.unix_timestamp;
 
+    // Index the chosen search terms
+    let search_terms = SearchIndex::tokenize(&[&track.title, &track.artist]);
+    track.search_entries_count = index_new_target(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        ctx.remaining_accounts,
+        &search_terms,
+        SearchIndex::TARGET_TRACK,
+        track.key(),
+        clock.unix_timestamp,
+    )?;
+
+    // Link to the album, replacing the free-text name
+    match (ctx.accounts.album_account.as_mut(), ctx.accounts.album_track.as_mut()) {
+        (Some(album_account), Some(album_track)) => {
+            append_album_track(album_account, album_track, track, clock.unix_timestamp)?;
+        }
+        (None, None) => {}
+        _ => return err!(SpotifyError::AlbumAccountsMissing),
+    }
+
     // Update user stats
     user_stats.tracks_created = user_stats.tracks_created.checked_add(1)
         .ok_or(SpotifyError::ArithmeticOverflow)?;
     user_stats.last_active = clock.unix_timestamp;
 
+    // Append to activity feed
+    activity_feed.record(
+        ctx.accounts.authority.key(),
+        ActivityFeed::ACTIVITY_TRACK_CREATED,
+        track.key(),
+        format!("Created track: {}", track.title),
+        clock.unix_timestamp,
+    );
+
+    emit!(TrackCreated {
+        track: track.key(),
+        creator: track.created_by,
+        title: track.title.clone(),
+        artist: track.artist.clone(),
+        artist_profile: track.artist_profile,
+        genre: track.genre.clone(),
+        price_lamports: track.price_lamports,
+        timestamp: clock.unix_timestamp,
+    });
+
     msg!("Track created: {} by {}", track.title, track.artist);
     Ok(())
 }
 
+/// Title and artist are part of the PDA seeds, so they cannot be changed here.
+#[allow(clippy::too_many_arguments)]
+pub fn update_track(
+    ctx: Context<UpdateTrack>,
+    album: Option<String>,
+    genre: Option<String>,
+    duration: Option<u64>,
+    audio_url: Option<String>,
+    cover_image: Option<String>,
+    price_lamports: Option<u64>,
+    is_premium: Option<bool>,
+) -> Result<()> {
+    let track = &mut ctx.accounts.track;
+    let user_stats = &mut ctx.accounts.user_stats;
+    let clock = Clock::get()?;
+
+    if let Some(album_name) = album {
+        // Linked tracks keep the name of their Album account
+        require!(track.album_key.is_none(), SpotifyError::TrackAlreadyInAlbum);
+        require!(album_name.len() <= 64, SpotifyError::AlbumNameTooLong);
+        track.album = album_name;
+    }
+
+    if let Some(genre_name) = genre {
+        require!(genre_name.len() <= 32, SpotifyError::GenreTooLong);
+        track.genre = genre_name;
+    }
+
+    if let Some(seconds) = duration {
+        require!(seconds > 0, SpotifyError::InvalidDuration);
+        track.duration = seconds;
+    }
+
+    if let Some(url) = audio_url {
+        require!(url.len() <= 256, SpotifyError::AudioUrlTooLong);
+        track.audio_url = url;
+    }
+
+    if let Some(image_url) = cover_image {
+        require!(image_url.len() <= 256, SpotifyError::CoverImageUrlTooLong);
+        track.cover_image = image_url;
+    }
+
+    if let Some(price) = price_lamports {
+        require!(price == 0 || ctx.accounts.creator_escrow.is_some(), SpotifyError::CreatorEscrowMissing);
+        track.price_lamports = price;
+    }
+
+    if let Some(premium) = is_premium {
+        track.is_premium = premium;
+    }
+
+    // Update last active
+    user_stats.last_active = clock.unix_timestamp;
+
+    emit!(TrackUpdated {
+        track: track.key(),
+        creator: track.created_by,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Track updated: {} by {}", track.title, track.artist);
+    Ok(())
+}
+
+/// Remaining accounts must hold every `TrackLike` of the track followed by the liking user,
+/// then every `TrackPlay` followed by its listener, then every `SearchIndex` entry.
+/// Each record refunds whoever paid for it.
+pub fn delete_track<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DeleteTrack<'info>>,
+) -> Result<()> {
+    let track = &ctx.accounts.track;
+    let user_stats = &mut ctx.accounts.user_stats;
+    let clock = Clock::get()?;
+
+    // Playlist entries belong to other playlists and would attach themselves to a new
+    // track created under the same title and artist
+    require!(track.playlists_count == 0, SpotifyError::TrackInUse);
+
+    // The album's ordered entry points at the track
+    require!(track.album_key.is_none(), SpotifyError::TrackInAlbum);
+
+    let remaining = ctx.remaining_accounts;
+    let likes_len = track.likes_count.checked_mul(2).ok_or(SpotifyError::ArithmeticOverflow)?;
+    let plays_len = track.listeners_count.checked_mul(2).ok_or(SpotifyError::ArithmeticOverflow)?;
+    let expected = likes_len.checked_add(plays_len).ok_or(SpotifyError::ArithmeticOverflow)?;
+    require!(remaining.len() as u64 >= expected, SpotifyError::TrackInUse);
+
+    let (like_accounts, rest) = remaining.split_at(likes_len as usize);
+    let (play_accounts, search_accounts) = rest.split_at(plays_len as usize);
+
+    // Closed accounts are no longer program-owned, so passing one twice fails the owner check
+    for pair in like_accounts.chunks(2) {
+        let (like_info, user_info) = (&pair[0], &pair[1]);
+        require!(like_info.owner == &crate::ID && like_info.is_writable, SpotifyError::InvalidAccount);
+
+        let like = TrackLike::try_deserialize(&mut &like_info.try_borrow_data()?[..])?;
+        require!(like.track == track.key(), SpotifyError::TrackInUse);
+        require!(
+            user_info.key() == like.user && user_info.is_writable,
+            SpotifyError::InvalidAccount
+        );
+        close_account(like_info, user_info)?;
+    }
+
+    for pair in play_accounts.chunks(2) {
+        let (play_info, user_info) = (&pair[0], &pair[1]);
+        require!(play_info.owner == &crate::ID && play_info.is_writable, SpotifyError::InvalidAccount);
+
+        let play = TrackPlay::try_deserialize(&mut &play_info.try_borrow_data()?[..])?;
+        require!(play.track == track.key(), SpotifyError::TrackInUse);
+        require!(
+            user_info.key() == play.user && user_info.is_writable,
+            SpotifyError::InvalidAccount
+        );
+        close_account(play_info, user_info)?;
+    }
+
+    close_search_entries(
+        track.key(),
+        track.search_entries_count,
+        search_accounts,
+        &ctx.accounts.authority.to_account_info(),
+    )?;
+
+    // Likes closed with the track no longer count towards the creator
+    user_stats.total_likes_received = user_stats.total_likes_received.checked_sub(track.likes_count)
+        .ok_or(SpotifyError::ArithmeticUnderflow)?;
+
+    // Update user stats
+    user_stats.tracks_created = user_stats.tracks_created.checked_sub(1)
+        .ok_or(SpotifyError::ArithmeticUnderflow)?;
+    user_stats.last_active = clock.unix_timestamp;
+
+    emit!(TrackDeleted {
+        track: track.key(),
+        creator: track.created_by,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Track deleted: {} by {}", track.title, track.artist);
+    Ok(())
+}
+
 pub fn play_track(
     ctx: Context<PlayTrack>,
     duration_played: u64,
@@ -54,8 +278,21 @@ pub fn play_track(
     let track_play = &mut ctx.accounts.track_play;
     let user_stats = &mut ctx.accounts.user_stats;
     let creator_stats = &mut ctx.accounts.creator_stats;
+    let activity_feed = &mut ctx.accounts.activity_feed;
     let clock = Clock::get()?;
 
+    require!(duration_played <= track.duration, SpotifyError::PlayDurationExceedsTrack);
+    require!(
+        duration_played >= TrackPlay::min_play_duration(track.duration),
+        SpotifyError::PlayTooShort
+    );
+    require_track_access(
+        track,
+        ctx.accounts.user.key(),
+        ctx.accounts.subscription.as_ref(),
+        clock.unix_timestamp,
+    )?;
+
     // Initialize or update the play record
     if track_play.track == Pubkey::default() {
         // First time playing this track
@@ -65,8 +302,16 @@ pub fn play_track(
         track_play.total_duration = duration_played;
         track_play.first_played_at = clock.unix_timestamp;
         track_play.last_played_at = clock.unix_timestamp;
+
+        // Count the new play record so delete_track can close it
+        track.listeners_count = track.listeners_count.checked_add(1)
+            .ok_or(SpotifyError::ArithmeticOverflow)?;
     } else {
         // Update existing play record
+        require!(
+            !track_play.is_cooling_down(clock.unix_timestamp, duration_played),
+            SpotifyError::PlayCooldownActive
+        );
         track_play.play_count = track_play.play_count.checked_add(1)
             .ok_or(SpotifyError::ArithmeticOverflow)?;
         track_play.total_duration = track_play.total_duration.checked_add(duration_played)
@@ -74,6 +319,20 @@ pub fn play_track(
         track_play.last_played_at = clock.unix_timestamp;
     }
 
+    // Charge for paid tracks, creators play their own tracks for free
+    let mut amount_paid = 0;
+    if track.price_lamports > 0 && ctx.accounts.user.key() != track.created_by {
+        collect_play_payment(
+            track.price_lamports,
+            &ctx.accounts.user,
+            ctx.accounts.creator_escrow.as_mut(),
+            ctx.accounts.config.as_ref(),
+            ctx.accounts.fee_recipient.as_ref(),
+            &ctx.accounts.system_program,
+        )?;
+        amount_paid = track.price_lamports;
+    }
+
     // Update track play count
     track.plays_count = track.plays_count.checked_add(1)
         .ok_or(SpotifyError::ArithmeticOverflow)?;
@@ -85,10 +344,77 @@ pub fn play_track(
     creator_stats.total_plays = creator_stats.total_plays.checked_add(1)
         .ok_or(SpotifyError::ArithmeticOverflow)?;
 
+    // Append to activity feed
+    activity_feed.record(
+        ctx.accounts.user.key(),
+        ActivityFeed::ACTIVITY_TRACK_PLAYED,
+        track.key(),
+        format!("Played track: {}", track.title),
+        clock.unix_timestamp,
+    );
+
+    emit!(TrackPlayed {
+        track: track.key(),
+        user: ctx.accounts.user.key(),
+        duration_played,
+        play_count: track_play.play_count,
+        amount_paid,
+        timestamp: clock.unix_timestamp,
+    });
+
     msg!("Track played: {} by {}", track.title, track.artist);
     Ok(())
 }
 
+pub fn tip_track(ctx: Context<TipTrack>, amount: u64) -> Result<()> {
+    require!(amount > 0, SpotifyError::InvalidTipAmount);
+
+    let track = &mut ctx.accounts.track;
+    let creator_stats = &mut ctx.accounts.creator_stats;
+    let activity_feed = &mut ctx.accounts.activity_feed;
+    let tipper = &ctx.accounts.tipper;
+    let clock = Clock::get()?;
+
+    require!(tipper.key() != track.created_by, SpotifyError::CannotTipOwnTrack);
+
+    // Transfer lamports from tipper to creator using CPI
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        anchor_lang::system_program::Transfer {
+            from: tipper.to_account_info(),
+            to: ctx.accounts.creator.to_account_info(),
+        },
+    );
+    anchor_lang::system_program::transfer(cpi_context, amount)?;
+
+    // Update tip totals
+    track.tips_received = track.tips_received.checked_add(amount)
+        .ok_or(SpotifyError::ArithmeticOverflow)?;
+    creator_stats.total_tips_received = creator_stats.total_tips_received.checked_add(amount)
+        .ok_or(SpotifyError::ArithmeticOverflow)?;
+
+    // Append to activity feed
+    activity_feed.record(
+        tipper.key(),
+        ActivityFeed::ACTIVITY_TRACK_TIPPED,
+        track.key(),
+        format!("Tipped track: {}", track.title),
+        clock.unix_timestamp,
+    );
+
+    emit!(TrackTipped {
+        track: track.key(),
+        tipper: tipper.key(),
+        creator: track.created_by,
+        amount,
+        total_tips: track.tips_received,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Track tipped: {} lamports to {}", amount, track.title);
+    Ok(())
+}
+
 #[derive(Accounts)]
 #[instruction(title: String, artist: String)]
 pub struct CreateTrack<'info> {
@@ -108,12 +434,117 @@ pub struct CreateTrack<'info> {
     )]
     pub user_stats: Account<'info, UserStats>,
 
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ActivityFeed::MAX_SIZE,
+        seeds = [b"activity_feed", authority.key().as_ref()],
+        bump
+    )]
+    pub activity_feed: Account<'info, ActivityFeed>,
+
+    // Only needed for paid tracks, so plays have somewhere to send the creator's share
+    #[account(
+        seeds = [b"creator_escrow", authority.key().as_ref()],
+        bump
+    )]
+    pub creator_escrow: Option<Account<'info, CreatorEscrow>>,
+
+    // Only needed to link the track to an artist profile
+    #[account(
+        seeds = [b"artist_profile", authority.key().as_ref()],
+        bump
+    )]
+    pub artist_profile: Option<Account<'info, ArtistProfile>>,
+
+    /// CHECK: Claim on the artist name, only present once a verified artist owns it
+    #[account(
+        seeds = [b"artist_name".as_ref(), ArtistNameClaim::seed(&artist).as_ref()],
+        bump
+    )]
+    pub artist_name_claim: UncheckedAccount<'info>,
+
+    // Only needed to link the track to an album
+    #[account(
+        mut,
+        seeds = [b"album", authority.key().as_ref(), album_account.title.as_bytes()],
+        bump
+    )]
+    pub album_account: Option<Box<Account<'info, Album>>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AlbumTrack::MAX_SIZE,
+        seeds = [b"album_track", linked_album_key(&album_account).as_ref(), &linked_album_position(&album_account)],
+        bump
+    )]
+    pub album_track: Option<Account<'info, AlbumTrack>>,
+
     #[account(mut)]
     pub authority: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
 
+// Seed helpers for the optional album link, which is validated in the handler
+fn linked_album_key(album: &Option<Box<Account<Album>>>) -> Pubkey {
+    album.as_ref().map(|album| album.key()).unwrap_or_default()
+}
+
+fn linked_album_position(album: &Option<Box<Account<Album>>>) -> [u8; 8] {
+    album.as_ref().map(|album| album.track_count).unwrap_or_default().to_le_bytes()
+}
+
+#[derive(Accounts)]
+pub struct UpdateTrack<'info> {
+    #[account(
+        mut,
+        seeds = [b"track", track.title.as_bytes(), track.artist.as_bytes()],
+        bump,
+        constraint = track.created_by == authority.key() @ SpotifyError::Unauthorized
+    )]
+    pub track: Box<Account<'info, Track>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", authority.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    // Only needed when setting a price
+    #[account(
+        seeds = [b"creator_escrow", authority.key().as_ref()],
+        bump
+    )]
+    pub creator_escrow: Option<Account<'info, CreatorEscrow>>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DeleteTrack<'info> {
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"track", track.title.as_bytes(), track.artist.as_bytes()],
+        bump,
+        constraint = track.created_by == authority.key() @ SpotifyError::Unauthorized
+    )]
+    pub track: Box<Account<'info, Track>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", authority.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct PlayTrack<'info> {
     #[account(mut)]
@@ -142,8 +573,71 @@ pub struct PlayTrack<'info> {
     )]
     pub creator_stats: Account<'info, UserStats>,
 
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + ActivityFeed::MAX_SIZE,
+        seeds = [b"activity_feed", user.key().as_ref()],
+        bump
+    )]
+    pub activity_feed: Account<'info, ActivityFeed>,
+
+    // Only needed for paid tracks
+    #[account(
+        mut,
+        seeds = [b"creator_escrow", track.created_by.as_ref()],
+        bump
+    )]
+    pub creator_escrow: Option<Account<'info, CreatorEscrow>>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Option<Account<'info, ProgramConfig>>,
+
+    #[account(mut)]
+    pub fee_recipient: Option<SystemAccount<'info>>,
+
+    // Only needed for premium tracks
+    #[account(
+        seeds = [b"subscription", user.key().as_ref()],
+        bump
+    )]
+    pub subscription: Option<Account<'info, Subscription>>,
+
     #[account(mut)]
     pub user: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
+
+#[derive(Accounts)]
+pub struct TipTrack<'info> {
+    #[account(mut)]
+    pub track: Box<Account<'info, Track>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", track.created_by.as_ref()],
+        bump
+    )]
+    pub creator_stats: Account<'info, UserStats>,
+
+    #[account(
+        mut,
+        address = track.created_by @ SpotifyError::InvalidAccount
+    )]
+    pub creator: SystemAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = tipper,
+        space = 8 + ActivityFeed::MAX_SIZE,
+        seeds = [b"activity_feed", tipper.key().as_ref()],
+        bump
+    )]
+    pub activity_feed: Account<'info, ActivityFeed>,
+
+    #[account(mut)]
+    pub tipper: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
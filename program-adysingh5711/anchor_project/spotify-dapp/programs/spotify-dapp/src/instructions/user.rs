@@ -1,6 +1,9 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::errors::SpotifyError;
+use crate::events::*;
+use crate::instructions::search::{close_search_entries, create_pda_account, index_new_target, prune_search_entries};
+use crate::instructions::playlist::close_account;
 
 #[derive(Accounts)]
 #[instruction(username: String)]
@@ -23,6 +26,15 @@ pub struct CreateUserProfile<'info> {
     )]
     pub user_stats: Account<'info, UserStats>,
 
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + UsernameClaim::MAX_SIZE,
+        seeds = [b"username", UsernameClaim::normalize(&username)?.as_bytes()],
+        bump
+    )]
+    pub username_claim: Account<'info, UsernameClaim>,
+
     #[account(mut)]
     pub authority: Signer<'info>,
 
@@ -49,8 +61,91 @@ pub struct UpdateUserProfile<'info> {
     pub authority: Signer<'info>,
 }
 
-pub fn create_user_profile(
-    ctx: Context<CreateUserProfile>,
+#[derive(Accounts)]
+#[instruction(new_username: String)]
+pub struct ChangeUsername<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_profile", authority.key().as_ref()],
+        bump,
+        constraint = user_profile.authority == authority.key() @ SpotifyError::Unauthorized
+    )]
+    pub user_profile: Box<Account<'info, UserProfile>>,
+
+    #[account(
+        mut,
+        seeds = [b"username", UsernameClaim::normalize(&user_profile.username)?.as_bytes()],
+        bump,
+        constraint = old_username_claim.profile == user_profile.key() @ SpotifyError::Unauthorized
+    )]
+    pub old_username_claim: Account<'info, UsernameClaim>,
+
+    /// CHECK: Created in the handler, unless only the case changes and it is the old claim
+    #[account(
+        mut,
+        seeds = [b"username", UsernameClaim::normalize(&new_username)?.as_bytes()],
+        bump
+    )]
+    pub new_username_claim: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseUserProfile<'info> {
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"user_profile", authority.key().as_ref()],
+        bump,
+        constraint = user_profile.authority == authority.key() @ SpotifyError::Unauthorized
+    )]
+    pub user_profile: Box<Account<'info, UserProfile>>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"user_stats", authority.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"username", UsernameClaim::normalize(&user_profile.username)?.as_bytes()],
+        bump,
+        constraint = username_claim.profile == user_profile.key() @ SpotifyError::Unauthorized
+    )]
+    pub username_claim: Account<'info, UsernameClaim>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"activity_feed", authority.key().as_ref()],
+        bump
+    )]
+    pub activity_feed: Option<Account<'info, ActivityFeed>>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"user_insights", authority.key().as_ref()],
+        bump
+    )]
+    pub user_insights: Option<Account<'info, UserInsights>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Remaining accounts are the `SearchIndex` PDAs of the username and display name terms
+/// to index, in `SearchIndex::tokenize` order.
+pub fn create_user_profile<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateUserProfile<'info>>,
     username: String,
     display_name: String,
     bio: String,
@@ -58,11 +153,12 @@ pub fn create_user_profile(
 ) -> Result<()> {
     let user_profile = &mut ctx.accounts.user_profile;
     let user_stats = &mut ctx.accounts.user_stats;
+    let username_claim = &mut ctx.accounts.username_claim;
     let clock = Clock::get()?;
 
     // Validate username length
     require!(username.len() <= 32, SpotifyError::UsernameTooLong);
-    require!(username.len() > 0, SpotifyError::UsernameEmpty);
+    require!(!username.is_empty(), SpotifyError::UsernameEmpty);
     require!(display_name.len() <= 64, SpotifyError::DisplayNameTooLong);
     require!(bio.len() <= 256, SpotifyError::BioTooLong);
     require!(profile_image.len() <= 256, SpotifyError::ProfileImageUrlTooLong);
@@ -82,12 +178,40 @@ pub fn create_user_profile(
     user_stats.playlists_created = 0;
     user_stats.total_likes_received = 0;
     user_stats.total_plays = 0;
+    user_stats.total_tips_received = 0;
     user_stats.last_active = clock.unix_timestamp;
 
+    // Claim the username
+    username_claim.profile = user_profile.key();
+    username_claim.authority = ctx.accounts.authority.key();
+    username_claim.username = UsernameClaim::normalize(&user_profile.username)?;
+    username_claim.created_at = clock.unix_timestamp;
+
+    // Index the chosen search terms
+    let search_terms = SearchIndex::tokenize(&[&user_profile.username, &user_profile.display_name]);
+    user_profile.search_entries_count = index_new_target(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        ctx.remaining_accounts,
+        &search_terms,
+        SearchIndex::TARGET_USER,
+        user_profile.key(),
+        clock.unix_timestamp,
+    )?;
+
+    emit!(UserProfileCreated {
+        profile: user_profile.key(),
+        authority: user_profile.authority,
+        username: user_profile.username.clone(),
+        timestamp: clock.unix_timestamp,
+    });
+
     msg!("User profile created for: {}", user_profile.username);
     Ok(())
 }
 
+/// Changing the display name takes every `SearchIndex` entry of the profile as remaining
+/// accounts and closes those whose term is gone.
 pub fn update_user_profile(
     ctx: Context<UpdateUserProfile>,
     display_name: Option<String>,
@@ -101,6 +225,16 @@ pub fn update_user_profile(
     if let Some(name) = display_name {
         require!(name.len() <= 64, SpotifyError::DisplayNameTooLong);
         user_profile.display_name = name;
+
+        // Drop the entries of terms that left the display name
+        let search_terms = SearchIndex::tokenize(&[&user_profile.username, &user_profile.display_name]);
+        user_profile.search_entries_count = prune_search_entries(
+            user_profile.key(),
+            user_profile.search_entries_count,
+            ctx.remaining_accounts,
+            &search_terms,
+            &ctx.accounts.authority.to_account_info(),
+        )?;
     }
 
     if let Some(bio_text) = bio {
@@ -116,6 +250,114 @@ pub fn update_user_profile(
     // Update last active
     user_stats.last_active = clock.unix_timestamp;
 
+    emit!(UserProfileUpdated {
+        profile: user_profile.key(),
+        authority: user_profile.authority,
+        timestamp: clock.unix_timestamp,
+    });
+
     msg!("User profile updated for: {}", user_profile.username);
     Ok(())
 }
+
+/// Takes every `SearchIndex` entry of the profile as remaining accounts and closes those
+/// whose term is gone.
+pub fn change_username(
+    ctx: Context<ChangeUsername>,
+    new_username: String,
+) -> Result<()> {
+    let user_profile = &mut ctx.accounts.user_profile;
+    let clock = Clock::get()?;
+
+    require!(new_username.len() <= 32, SpotifyError::UsernameTooLong);
+    require!(!new_username.is_empty(), SpotifyError::UsernameEmpty);
+
+    // Drop the entries of terms that left the username
+    let search_terms = SearchIndex::tokenize(&[&new_username, &user_profile.display_name]);
+    user_profile.search_entries_count = prune_search_entries(
+        user_profile.key(),
+        user_profile.search_entries_count,
+        ctx.remaining_accounts,
+        &search_terms,
+        &ctx.accounts.authority.to_account_info(),
+    )?;
+
+    // A case-only rename keeps the same claim
+    let normalized = UsernameClaim::normalize(&new_username)?;
+    if normalized != ctx.accounts.old_username_claim.username {
+        let claim_info = ctx.accounts.new_username_claim.to_account_info();
+        require!(claim_info.owner != &crate::ID, SpotifyError::UsernameTaken);
+
+        create_pda_account(
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &claim_info,
+            8 + UsernameClaim::MAX_SIZE,
+            &[b"username", normalized.as_bytes(), &[ctx.bumps.new_username_claim]],
+        )?;
+
+        let new_username_claim = UsernameClaim {
+            profile: user_profile.key(),
+            authority: ctx.accounts.authority.key(),
+            username: normalized,
+            created_at: clock.unix_timestamp,
+        };
+        new_username_claim.try_serialize(&mut &mut claim_info.try_borrow_mut_data()?[..])?;
+
+        // Release the previous name
+        close_account(
+            &ctx.accounts.old_username_claim.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+        )?;
+    }
+
+    emit!(UsernameChanged {
+        profile: user_profile.key(),
+        authority: user_profile.authority,
+        old_username: user_profile.username.clone(),
+        new_username: new_username.clone(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Username changed from {} to {}", user_profile.username, new_username);
+    user_profile.username = new_username;
+    Ok(())
+}
+
+/// Outgoing follows must be removed with `unfollow_user` first so other profiles keep
+/// accurate `followers_count` values, and every track and playlist must be deleted.
+/// Incoming `UserFollow` records are left behind and can be closed by their followers
+/// with `remove_orphaned_follow`. An `ArtistProfile` is kept along with its verification;
+/// it points at the same profile PDA again once the wallet creates a new profile.
+/// Remaining accounts must hold every `SearchIndex` entry of the profile.
+pub fn close_user_profile<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CloseUserProfile<'info>>,
+) -> Result<()> {
+    let user_profile = &ctx.accounts.user_profile;
+    let user_stats = &ctx.accounts.user_stats;
+    let clock = Clock::get()?;
+
+    require!(user_profile.following_count == 0, SpotifyError::ProfileStillFollowing);
+
+    // Tracks and playlists keep their creator's stats up to date, so they must go first
+    require!(
+        user_stats.tracks_created == 0 && user_stats.playlists_created == 0,
+        SpotifyError::ProfileStillHasContent
+    );
+
+    close_search_entries(
+        user_profile.key(),
+        user_profile.search_entries_count,
+        ctx.remaining_accounts,
+        &ctx.accounts.authority.to_account_info(),
+    )?;
+
+    emit!(UserProfileClosed {
+        profile: user_profile.key(),
+        authority: user_profile.authority,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("User profile closed for: {}", user_profile.username);
+    Ok(())
+}
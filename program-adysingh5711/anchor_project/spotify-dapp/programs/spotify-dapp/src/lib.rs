@@ -1,13 +1,18 @@
 #![allow(deprecated)]
+#![allow(unexpected_cfgs)]
 use anchor_lang::prelude::*;
 
 // Import modules
 pub mod state;
 pub mod errors;
+pub mod events;
+pub mod instructions;
 
 // Re-export types
 pub use state::*;
 pub use errors::*;
+pub use events::*;
+use instructions::*;
 
 declare_id!("cMq3jX2jiQJTCMJPAAj6BT48WqCiJhT2yQE9BzSRr2X");
 
@@ -16,47 +21,37 @@ pub mod spotify_program {
     use super::*;
 
     // User Management Instructions
-    pub fn create_user_profile(
-        ctx: Context<CreateUserProfile>,
+    pub fn create_user_profile<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateUserProfile<'info>>,
         username: String,
         display_name: String,
         bio: String,
         profile_image: String,
     ) -> Result<()> {
-        // Validate inputs first to avoid unnecessary work
-        require!(username.len() <= 32 && !username.is_empty(), SpotifyError::UsernameTooLong);
-        require!(display_name.len() <= 64, SpotifyError::DisplayNameTooLong);
-        require!(bio.len() <= 256, SpotifyError::BioTooLong);
-        require!(profile_image.len() <= 256, SpotifyError::ProfileImageUrlTooLong);
-
-        let user_profile = &mut ctx.accounts.user_profile;
-        let user_stats = &mut ctx.accounts.user_stats;
-        let clock = Clock::get()?;
-        let authority_key = ctx.accounts.authority.key();
-
-        user_profile.authority = authority_key;
-        user_profile.username = username;
-        user_profile.display_name = display_name;
-        user_profile.bio = bio;
-        user_profile.profile_image = profile_image;
-        user_profile.followers_count = 0;
-        user_profile.following_count = 0;
-        user_profile.created_at = clock.unix_timestamp;
-
-        // Initialize user stats
-        user_stats.user = user_profile.key();
-        user_stats.tracks_created = 0;
-        user_stats.playlists_created = 0;
-        user_stats.total_likes_received = 0;
-        user_stats.total_plays = 0;
-        user_stats.last_active = clock.unix_timestamp;
-
-        msg!("User profile created for: {}", user_profile.username);
-        Ok(())
-    }
-
-    pub fn create_track(
-        ctx: Context<CreateTrack>,
+        instructions::user::create_user_profile(ctx, username, display_name, bio, profile_image)
+    }
+
+    pub fn update_user_profile(
+        ctx: Context<UpdateUserProfile>,
+        display_name: Option<String>,
+        bio: Option<String>,
+        profile_image: Option<String>,
+    ) -> Result<()> {
+        instructions::user::update_user_profile(ctx, display_name, bio, profile_image)
+    }
+
+    pub fn change_username(ctx: Context<ChangeUsername>, new_username: String) -> Result<()> {
+        instructions::user::change_username(ctx, new_username)
+    }
+
+    pub fn close_user_profile<'info>(ctx: Context<'_, '_, 'info, 'info, CloseUserProfile<'info>>) -> Result<()> {
+        instructions::user::close_user_profile(ctx)
+    }
+
+    // Track Instructions
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_track<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateTrack<'info>>,
         title: String,
         artist: String,
         album: String,
@@ -64,92 +59,229 @@ pub mod spotify_program {
         duration: u64,
         audio_url: String,
         cover_image: String,
+        price_lamports: u64,
+        is_premium: bool,
     ) -> Result<()> {
-        // Validate inputs first
-        require!(title.len() <= 128 && !title.is_empty(), SpotifyError::TrackTitleTooLong);
-        require!(artist.len() <= 64, SpotifyError::ArtistNameTooLong);
-        require!(album.len() <= 64, SpotifyError::AlbumNameTooLong);
-        require!(genre.len() <= 32, SpotifyError::GenreTooLong);
-        require!(audio_url.len() <= 256, SpotifyError::AudioUrlTooLong);
-        require!(cover_image.len() <= 256, SpotifyError::CoverImageUrlTooLong);
-        require!(duration > 0, SpotifyError::InvalidDuration);
-
-        let track = &mut ctx.accounts.track;
-        let user_stats = &mut ctx.accounts.user_stats;
-        let clock = Clock::get()?;
-        let authority_key = ctx.accounts.authority.key();
-
-        track.title = title;
-        track.artist = artist;
-        track.album = album;
-        track.genre = genre;
-        track.duration = duration;
-        track.audio_url = audio_url;
-        track.cover_image = cover_image;
-        track.likes_count = 0;
-        track.plays_count = 0;
-        track.created_by = authority_key;
-        track.created_at = clock.unix_timestamp;
-
-        // Update user stats
-        user_stats.tracks_created = user_stats.tracks_created.checked_add(1)
-            .ok_or(SpotifyError::ArithmeticOverflow)?;
-        user_stats.last_active = clock.unix_timestamp;
-
-        msg!("Track created: {} by {}", track.title, track.artist);
-        Ok(())
+        instructions::track::create_track(ctx, title, artist, album, genre, duration, audio_url, cover_image, price_lamports, is_premium)
     }
-}
 
-// Account Structures
-#[derive(Accounts)]
-#[instruction(username: String)]
-pub struct CreateUserProfile<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + UserProfile::MAX_SIZE,
-        seeds = [b"user_profile", authority.key().as_ref()],
-        bump
-    )]
-    pub user_profile: Box<Account<'info, UserProfile>>,
-
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + UserStats::MAX_SIZE,
-        seeds = [b"user_stats", authority.key().as_ref()],
-        bump
-    )]
-    pub user_stats: Account<'info, UserStats>,
-
-    #[account(mut)]
-    pub authority: Signer<'info>,
-
-    pub system_program: Program<'info, System>,
-}
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_track(
+        ctx: Context<UpdateTrack>,
+        album: Option<String>,
+        genre: Option<String>,
+        duration: Option<u64>,
+        audio_url: Option<String>,
+        cover_image: Option<String>,
+        price_lamports: Option<u64>,
+        is_premium: Option<bool>,
+    ) -> Result<()> {
+        instructions::track::update_track(ctx, album, genre, duration, audio_url, cover_image, price_lamports, is_premium)
+    }
+
+    pub fn delete_track<'info>(ctx: Context<'_, '_, 'info, 'info, DeleteTrack<'info>>) -> Result<()> {
+        instructions::track::delete_track(ctx)
+    }
+
+    pub fn play_track(ctx: Context<PlayTrack>, duration_played: u64) -> Result<()> {
+        instructions::track::play_track(ctx, duration_played)
+    }
+
+    pub fn tip_track(ctx: Context<TipTrack>, amount: u64) -> Result<()> {
+        instructions::track::tip_track(ctx, amount)
+    }
+
+    // Artist Instructions
+    pub fn create_artist_profile(ctx: Context<CreateArtistProfile>, name: String) -> Result<()> {
+        instructions::artist::create_artist_profile(ctx, name)
+    }
+
+    pub fn verify_artist(ctx: Context<VerifyArtist>, is_verified: bool) -> Result<()> {
+        instructions::artist::verify_artist(ctx, is_verified)
+    }
+
+    // Album Instructions
+    pub fn create_album(
+        ctx: Context<CreateAlbum>,
+        title: String,
+        cover_image: String,
+        released_at: Option<i64>,
+    ) -> Result<()> {
+        instructions::album::create_album(ctx, title, cover_image, released_at)
+    }
+
+    pub fn add_track_to_album(ctx: Context<AddTrackToAlbum>) -> Result<()> {
+        instructions::album::add_track_to_album(ctx)
+    }
+
+    // Playlist Instructions
+    pub fn create_playlist<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreatePlaylist<'info>>,
+        name: String,
+        description: String,
+        is_public: bool,
+        is_collaborative: bool,
+    ) -> Result<()> {
+        instructions::playlist::create_playlist(ctx, name, description, is_public, is_collaborative)
+    }
+
+    pub fn update_playlist(
+        ctx: Context<UpdatePlaylist>,
+        description: Option<String>,
+        is_public: Option<bool>,
+        is_collaborative: Option<bool>,
+    ) -> Result<()> {
+        instructions::playlist::update_playlist(ctx, description, is_public, is_collaborative)
+    }
+
+    pub fn delete_playlist<'info>(ctx: Context<'_, '_, 'info, 'info, DeletePlaylist<'info>>) -> Result<()> {
+        instructions::playlist::delete_playlist(ctx)
+    }
+
+    pub fn add_track_to_playlist(ctx: Context<AddTrackToPlaylist>) -> Result<()> {
+        instructions::playlist::add_track_to_playlist(ctx)
+    }
+
+    pub fn remove_track_from_playlist(ctx: Context<RemoveTrackFromPlaylist>) -> Result<()> {
+        instructions::playlist::remove_track_from_playlist(ctx)
+    }
+
+    pub fn move_playlist_track(ctx: Context<MovePlaylistTrack>, new_position: u64) -> Result<()> {
+        instructions::playlist::move_playlist_track(ctx, new_position)
+    }
+
+    pub fn add_collaborator(ctx: Context<AddCollaborator>, permissions: u8) -> Result<()> {
+        instructions::playlist::add_collaborator(ctx, permissions)
+    }
+
+    pub fn update_collaborator_permissions(
+        ctx: Context<UpdateCollaboratorPermissions>,
+        permissions: u8,
+    ) -> Result<()> {
+        instructions::playlist::update_collaborator_permissions(ctx, permissions)
+    }
+
+    pub fn remove_collaborator(ctx: Context<RemoveCollaborator>) -> Result<()> {
+        instructions::playlist::remove_collaborator(ctx)
+    }
+
+    // Social Instructions
+    pub fn like_track(ctx: Context<LikeTrack>) -> Result<()> {
+        instructions::social::like_track(ctx)
+    }
+
+    pub fn unlike_track(ctx: Context<UnlikeTrack>) -> Result<()> {
+        instructions::social::unlike_track(ctx)
+    }
+
+    pub fn like_playlist(ctx: Context<LikePlaylist>) -> Result<()> {
+        instructions::social::like_playlist(ctx)
+    }
+
+    pub fn unlike_playlist(ctx: Context<UnlikePlaylist>) -> Result<()> {
+        instructions::social::unlike_playlist(ctx)
+    }
+
+    pub fn follow_user(ctx: Context<FollowUser>) -> Result<()> {
+        instructions::social::follow_user(ctx)
+    }
+
+    pub fn unfollow_user(ctx: Context<UnfollowUser>) -> Result<()> {
+        instructions::social::unfollow_user(ctx)
+    }
+
+    pub fn remove_orphaned_follow(ctx: Context<RemoveOrphanedFollow>) -> Result<()> {
+        instructions::social::remove_orphaned_follow(ctx)
+    }
+
+    pub fn migrate_activity_feed(ctx: Context<MigrateActivityFeed>) -> Result<()> {
+        instructions::social::migrate_activity_feed(ctx)
+    }
+
+    // Search Instructions
+    pub fn create_search_index(
+        ctx: Context<CreateSearchIndex>,
+        search_term: String,
+        target_type: u8,
+        target_pubkey: Pubkey,
+    ) -> Result<()> {
+        instructions::search::create_search_index(ctx, search_term, target_type, target_pubkey)
+    }
+
+    pub fn remove_search_index(ctx: Context<RemoveSearchIndex>) -> Result<()> {
+        instructions::search::remove_search_index(ctx)
+    }
+
+    pub fn update_search_index(ctx: Context<UpdateSearchIndex>, new_term: String) -> Result<()> {
+        instructions::search::update_search_index(ctx, new_term)
+    }
+
+    // Analytics Instructions
+    pub fn generate_user_insights(ctx: Context<GenerateUserInsights>) -> Result<()> {
+        instructions::analytics::generate_user_insights(ctx)
+    }
+
+    pub fn create_recommendation(
+        ctx: Context<CreateRecommendation>,
+        recommendation_type: u8,
+        target: Pubkey,
+        score: f32,
+        reason: String,
+    ) -> Result<()> {
+        instructions::analytics::create_recommendation(ctx, recommendation_type, target, score, reason)
+    }
+
+    pub fn mark_recommendation_viewed(ctx: Context<MarkRecommendationViewed>) -> Result<()> {
+        instructions::analytics::mark_recommendation_viewed(ctx)
+    }
+
+    pub fn dismiss_recommendation(ctx: Context<DismissRecommendation>) -> Result<()> {
+        instructions::analytics::dismiss_recommendation(ctx)
+    }
+
+    pub fn expire_recommendation(ctx: Context<ExpireRecommendation>) -> Result<()> {
+        instructions::analytics::expire_recommendation(ctx)
+    }
+
+    // Config Instructions
+    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+        instructions::config::initialize_config(ctx)
+    }
+
+    pub fn add_recommender(ctx: Context<UpdateRecommenders>, recommender: Pubkey) -> Result<()> {
+        instructions::config::add_recommender(ctx, recommender)
+    }
+
+    pub fn remove_recommender(ctx: Context<UpdateRecommenders>, recommender: Pubkey) -> Result<()> {
+        instructions::config::remove_recommender(ctx, recommender)
+    }
 
-#[derive(Accounts)]
-#[instruction(title: String, artist: String)]
-pub struct CreateTrack<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + Track::MAX_SIZE,
-        seeds = [b"track", authority.key().as_ref(), title.as_bytes(), artist.as_bytes()],
-        bump
-    )]
-    pub track: Account<'info, Track>,
-
-    #[account(
-        mut,
-        seeds = [b"user_stats", authority.key().as_ref()],
-        bump
-    )]
-    pub user_stats: Account<'info, UserStats>,
-
-    #[account(mut)]
-    pub authority: Signer<'info>,
-
-    pub system_program: Program<'info, System>,
-}
\ No newline at end of file
+    pub fn set_platform_fee(
+        ctx: Context<UpdatePricing>,
+        platform_fee_bps: u16,
+        fee_recipient: Pubkey,
+    ) -> Result<()> {
+        instructions::config::set_platform_fee(ctx, platform_fee_bps, fee_recipient)
+    }
+
+    pub fn set_subscription_price(ctx: Context<UpdatePricing>, subscription_price_lamports: u64) -> Result<()> {
+        instructions::config::set_subscription_price(ctx, subscription_price_lamports)
+    }
+
+    // Payment Instructions
+    pub fn init_creator_escrow(ctx: Context<InitCreatorEscrow>) -> Result<()> {
+        instructions::payments::init_creator_escrow(ctx)
+    }
+
+    pub fn withdraw_earnings(ctx: Context<WithdrawEarnings>) -> Result<()> {
+        instructions::payments::withdraw_earnings(ctx)
+    }
+
+    pub fn subscribe(ctx: Context<Subscribe>, periods: u8) -> Result<()> {
+        instructions::payments::subscribe(ctx, periods)
+    }
+
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        instructions::payments::withdraw_treasury(ctx, amount)
+    }
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct Album {
+    pub creator: Pubkey,          // Creator's wallet (32 bytes)
+    pub title: String,            // Album title (4 + 64 = 68 bytes)
+    pub cover_image: String,      // Album cover URL (4 + 256 = 260 bytes)
+    pub released_at: i64,         // Release timestamp (8 bytes)
+    pub track_count: u64,         // Number of tracks (8 bytes)
+    pub created_at: i64,          // Timestamp (8 bytes)
+}
+
+impl Album {
+    pub const MAX_SIZE: usize = 32 + 68 + 260 + 8 + 8 + 8; // 384 bytes
+}
+
+/// Track at `position` on an album. Seeded by position, so a client can list
+/// the tracks in order by deriving `0..track_count`.
+#[account]
+pub struct AlbumTrack {
+    pub album: Pubkey,            // Album PDA (32 bytes)
+    pub track: Pubkey,            // Track PDA (32 bytes)
+    pub position: u64,            // Zero-based track number (8 bytes)
+    pub added_at: i64,            // Timestamp (8 bytes)
+}
+
+impl AlbumTrack {
+    pub const MAX_SIZE: usize = 32 + 32 + 8 + 8; // 80 bytes
+}
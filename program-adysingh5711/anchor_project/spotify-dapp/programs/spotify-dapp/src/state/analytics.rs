@@ -13,6 +13,9 @@ pub struct UserInsights {
 
 impl UserInsights {
     pub const MAX_SIZE: usize = 32 + 8 + 36 + 33 + 4 + 4 + 8; // 125 bytes
+
+    // Interactions at which the social engagement score reaches 0.5
+    pub const SOCIAL_ENGAGEMENT_MIDPOINT: f32 = 20.0;
 }
 
 #[account]
@@ -24,25 +27,89 @@ pub struct Recommendation {
     pub reason: String,                  // Why this was recommended (4 + 128 = 132 bytes)
     pub created_at: i64,                 // When created (8 bytes)
     pub is_viewed: bool,                 // Has user seen this (1 byte)
+    pub payer: Pubkey,                   // Who paid the rent (user or recommender) (32 bytes)
 }
 
 impl Recommendation {
-    pub const MAX_SIZE: usize = 32 + 1 + 32 + 4 + 132 + 8 + 1; // 210 bytes
+    pub const MAX_SIZE: usize = 32 + 1 + 32 + 4 + 132 + 8 + 1 + 32; // 242 bytes
     
     // Recommendation types
     pub const TYPE_TRACK: u8 = 1;
     pub const TYPE_PLAYLIST: u8 = 2;
     pub const TYPE_USER: u8 = 3;
+
+    // Recommendations older than this can be closed by anyone
+    pub const EXPIRY_SECONDS: i64 = 30 * 24 * 60 * 60;
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.created_at.saturating_add(Self::EXPIRY_SECONDS)
+    }
 }
 
+/// One account per (normalised term, target) pair, so every index account has a fixed
+/// size. Clients list the targets of a term with a memcmp filter on `search_term`.
 #[account]
 pub struct SearchIndex {
-    pub search_term: String,      // Searchable term (4 + 64 = 68 bytes)
-    pub target_type: u8,          // Type of target (1 byte)
-    pub target_pubkey: Pubkey,    // Target account (32 bytes)
-    pub created_at: i64,          // When indexed (8 bytes)
+    pub search_term: String,          // Searchable term (4 + 32 = 36 bytes)
+    pub target_type: u8,              // Type of target (1 byte)
+    pub target_pubkey: Pubkey,        // Target account (32 bytes)
+    pub creator: Pubkey,              // Wallet that created the target and paid the rent (32 bytes)
+    pub created_at: i64,              // When indexed (8 bytes)
 }
 
 impl SearchIndex {
-    pub const MAX_SIZE: usize = 68 + 1 + 32 + 8; // 109 bytes
-}
\ No newline at end of file
+    pub const MAX_SIZE: usize = 36 + 1 + 32 + 32 + 8; // 109 bytes
+    pub const MAX_TERM_LEN: usize = 32;
+    pub const MIN_PREFIX_LEN: usize = 2;
+    pub const MAX_PREFIX_LEN: usize = 6;
+    pub const MAX_TOKENS: usize = 16;
+
+    // Target types
+    pub const TARGET_TRACK: u8 = 1;
+    pub const TARGET_PLAYLIST: u8 = 2;
+    pub const TARGET_USER: u8 = 3;
+
+    /// Splits `texts` into lowercased words, followed by their prefixes of
+    /// `MIN_PREFIX_LEN..=MAX_PREFIX_LEN` characters. Whole words come first so the
+    /// `MAX_TOKENS` cap drops prefixes before words. A target can only be indexed
+    /// under terms from this list.
+    pub fn tokenize(texts: &[&str]) -> Vec<String> {
+        let mut tokens: Vec<String> = Vec::new();
+
+        for text in texts {
+            for word in text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()) {
+                let word = Self::normalize(word);
+                if !tokens.contains(&word) {
+                    tokens.push(word);
+                }
+            }
+        }
+
+        let words = tokens.clone();
+        for word in &words {
+            let char_count = word.chars().count();
+            for len in Self::MIN_PREFIX_LEN..=Self::MAX_PREFIX_LEN.min(char_count.saturating_sub(1)) {
+                let prefix: String = word.chars().take(len).collect();
+                if !tokens.contains(&prefix) {
+                    tokens.push(prefix);
+                }
+            }
+        }
+
+        tokens.truncate(Self::MAX_TOKENS);
+        tokens
+    }
+
+    /// Lowercases `term` and cuts it to `MAX_TERM_LEN` bytes so it fits in a PDA seed.
+    pub fn normalize(term: &str) -> String {
+        let mut term = term.to_lowercase();
+        if term.len() > Self::MAX_TERM_LEN {
+            let mut end = Self::MAX_TERM_LEN;
+            while !term.is_char_boundary(end) {
+                end -= 1;
+            }
+            term.truncate(end);
+        }
+        term
+    }
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+/// Public artist identity of a user. Only the program admin can verify it.
+#[account]
+pub struct ArtistProfile {
+    pub authority: Pubkey,        // Artist's wallet (32 bytes)
+    pub user_profile: Pubkey,     // Linked user profile (32 bytes)
+    pub name: String,             // Artist name (4 + 64 = 68 bytes)
+    pub is_verified: bool,        // Verified by the program admin (1 byte)
+    pub verified_at: i64,         // When last verified, 0 if never (8 bytes)
+    pub created_at: i64,          // Timestamp (8 bytes)
+}
+
+impl ArtistProfile {
+    pub const MAX_SIZE: usize = 32 + 32 + 68 + 1 + 8 + 8; // 149 bytes
+}
+
+/// Reserves a verified artist's name, so other tracks cannot use it as their artist
+/// without linking that artist's profile.
+#[account]
+pub struct ArtistNameClaim {
+    pub artist_profile: Pubkey,   // Verified artist profile owning the name (32 bytes)
+    pub name: String,             // Artist name as verified (4 + 64 = 68 bytes)
+    pub created_at: i64,          // Timestamp (8 bytes)
+}
+
+impl ArtistNameClaim {
+    pub const MAX_SIZE: usize = 32 + 68 + 8; // 108 bytes
+
+    /// Names are claimed case-insensitively. They can be longer than a seed, so claims
+    /// are keyed on the hash of the lowercased name.
+    pub fn seed(name: &str) -> [u8; 32] {
+        anchor_lang::solana_program::hash::hash(name.to_lowercase().as_bytes()).to_bytes()
+    }
+}
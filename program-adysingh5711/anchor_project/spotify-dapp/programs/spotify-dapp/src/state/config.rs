@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct ProgramConfig {
+    pub admin: Pubkey,                 // Program admin (32 bytes)
+    pub recommenders: Vec<Pubkey>,     // Authorities allowed to push recommendations (4 + 10 * 32 = 324 bytes)
+    pub created_at: i64,               // Timestamp (8 bytes)
+    pub platform_fee_bps: u16,         // Platform share of paid plays, in basis points (2 bytes)
+    pub fee_recipient: Pubkey,         // Wallet receiving platform fees (32 bytes)
+    pub subscription_price_lamports: u64, // Price of one subscription period, 0 disables subscribing (8 bytes)
+}
+
+impl ProgramConfig {
+    pub const MAX_RECOMMENDERS: usize = 10;
+    pub const MAX_SIZE: usize = 32 + 4 + Self::MAX_RECOMMENDERS * 32 + 8 + 2 + 32 + 8; // 406 bytes
+
+    pub const BPS_DENOMINATOR: u64 = 10_000;
+    pub const MAX_PLATFORM_FEE_BPS: u16 = 3_000;
+
+    pub fn is_recommender(&self, key: &Pubkey) -> bool {
+        self.recommenders.contains(key)
+    }
+
+    /// Platform share of `amount`, rounded down.
+    pub fn platform_fee(&self, amount: u64) -> Option<u64> {
+        let fee = (amount as u128).checked_mul(self.platform_fee_bps as u128)? / Self::BPS_DENOMINATOR as u128;
+        u64::try_from(fee).ok()
+    }
+}
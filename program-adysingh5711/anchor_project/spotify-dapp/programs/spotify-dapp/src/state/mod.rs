@@ -1,8 +1,12 @@
-pub mod user;
-pub mod playlist;
-pub mod track;
-pub mod social;
-pub mod analytics;
+mod user;
+mod playlist;
+mod track;
+mod social;
+mod analytics;
+mod config;
+mod payments;
+mod album;
+mod artist;
 
 // Re-export all state structs
 pub use user::*;
@@ -10,3 +14,7 @@ pub use playlist::*;
 pub use track::*;
 pub use social::*;
 pub use analytics::*;
+pub use config::*;
+pub use payments::*;
+pub use album::*;
+pub use artist::*;
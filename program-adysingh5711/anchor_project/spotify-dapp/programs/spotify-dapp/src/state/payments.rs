@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+/// Holds a creator's share of paid plays until they withdraw it.
+/// Earnings are kept as lamports on this account on top of its rent.
+#[account]
+pub struct CreatorEscrow {
+    pub creator: Pubkey,          // Creator's wallet (32 bytes)
+    pub total_earned: u64,        // Lamports earned from paid plays (8 bytes)
+    pub total_withdrawn: u64,     // Lamports withdrawn so far (8 bytes)
+    pub created_at: i64,          // Timestamp (8 bytes)
+}
+
+impl CreatorEscrow {
+    pub const MAX_SIZE: usize = 32 + 8 + 8 + 8; // 56 bytes
+
+    pub fn available(&self) -> u64 {
+        self.total_earned.saturating_sub(self.total_withdrawn)
+    }
+}
+
+/// Premium access for a listener until `expires_at`.
+#[account]
+pub struct Subscription {
+    pub user: Pubkey,             // Subscriber's wallet (32 bytes)
+    pub expires_at: i64,          // End of the paid period (8 bytes)
+    pub total_paid: u64,          // Lamports paid over all periods (8 bytes)
+    pub created_at: i64,          // Timestamp (8 bytes)
+}
+
+impl Subscription {
+    pub const MAX_SIZE: usize = 32 + 8 + 8 + 8; // 56 bytes
+
+    pub const PERIOD_SECONDS: i64 = 30 * 24 * 60 * 60;
+    pub const MAX_PERIODS: u8 = 12;
+
+    pub fn is_active(&self, now: i64) -> bool {
+        now < self.expires_at
+    }
+}
+
+/// Collects subscription payments. Lamports are kept on this account on top of its rent.
+#[account]
+pub struct Treasury {
+    pub total_collected: u64,     // Lamports collected from subscriptions (8 bytes)
+    pub created_at: i64,          // Timestamp (8 bytes)
+}
+
+impl Treasury {
+    pub const MAX_SIZE: usize = 8 + 8; // 16 bytes
+}
@@ -12,10 +12,12 @@ pub struct Playlist {
     pub is_collaborative: bool,   // Can others add tracks (1 byte)
     pub created_at: i64,          // Timestamp (8 bytes)
     pub updated_at: i64,          // Last update timestamp (8 bytes)
+    pub collaborators_count: u64, // Number of collaborators (8 bytes)
+    pub search_entries_count: u64, // Number of search index accounts pointing here (8 bytes)
 }
 
 impl Playlist {
-    pub const MAX_SIZE: usize = 8 + 32 + 68 + 260 + 1 + 8 + 8 + 8 + 1 + 8 + 8; // 408 bytes
+    pub const MAX_SIZE: usize = 8 + 32 + 68 + 260 + 1 + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 8; // 424 bytes
 }
 
 #[account]
@@ -47,4 +49,8 @@ impl PlaylistCollaborator {
     pub const PERMISSION_REMOVE_TRACKS: u8 = 2;
     pub const PERMISSION_EDIT_INFO: u8 = 4;
     pub const PERMISSION_ALL: u8 = 7;
+
+    pub fn has_permission(&self, permission: u8) -> bool {
+        self.permissions & permission == permission
+    }
 }
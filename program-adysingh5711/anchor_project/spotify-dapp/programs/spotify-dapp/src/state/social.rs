@@ -33,8 +33,23 @@ impl UserFollow {
     pub const MAX_SIZE: usize = 8 + 32 + 32 + 8; // 80 bytes
 }
 
-#[account]
-pub struct ActivityFeed {
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ActivityEntry {
+    pub activity_type: u8,        // Type of activity (1 byte)
+    pub target: Pubkey,           // Target of activity (track/playlist/user) (32 bytes)
+    pub metadata: String,         // Additional data (4 + 64 = 68 bytes)
+    pub created_at: i64,          // When activity occurred (8 bytes)
+}
+
+impl ActivityEntry {
+    pub const MAX_SIZE: usize = 1 + 32 + 68 + 8; // 109 bytes
+    pub const MAX_METADATA_LEN: usize = 64;
+}
+
+/// Single-entry feed layout used before the ring buffer. Only read by
+/// `migrate_activity_feed`, which converts it in place.
+#[derive(AnchorDeserialize)]
+pub struct LegacyActivityFeed {
     pub user: Pubkey,             // User this activity belongs to (32 bytes)
     pub activity_type: u8,        // Type of activity (1 byte)
     pub target: Pubkey,           // Target of activity (track/playlist/user) (32 bytes)
@@ -42,9 +57,23 @@ pub struct ActivityFeed {
     pub created_at: i64,          // When activity occurred (8 bytes)
 }
 
+impl LegacyActivityFeed {
+    pub const ACCOUNT_LEN: usize = 8 + 8 + 32 + 1 + 32 + 68 + 8; // 157 bytes
+}
+
+/// Ring buffer of a user's most recent activities. `head` counts every entry ever
+/// written, so the newest entry lives at `(head - 1) % CAPACITY` once the buffer wraps.
+#[account]
+pub struct ActivityFeed {
+    pub user: Pubkey,                 // User this activity belongs to (32 bytes)
+    pub head: u64,                    // Total number of entries written (8 bytes)
+    pub entries: Vec<ActivityEntry>,  // Recent activities (4 + 32 * 109 = 3492 bytes)
+}
+
 impl ActivityFeed {
-    pub const MAX_SIZE: usize = 8 + 32 + 1 + 32 + 68 + 8; // 149 bytes
-    
+    pub const CAPACITY: usize = 32;
+    pub const MAX_SIZE: usize = 32 + 8 + 4 + Self::CAPACITY * ActivityEntry::MAX_SIZE; // 3532 bytes
+
     // Activity types
     pub const ACTIVITY_TRACK_LIKED: u8 = 1;
     pub const ACTIVITY_PLAYLIST_LIKED: u8 = 2;
@@ -52,4 +81,45 @@ impl ActivityFeed {
     pub const ACTIVITY_TRACK_CREATED: u8 = 4;
     pub const ACTIVITY_PLAYLIST_CREATED: u8 = 5;
     pub const ACTIVITY_TRACK_PLAYED: u8 = 6;
+    pub const ACTIVITY_TRACK_UNLIKED: u8 = 7;
+    pub const ACTIVITY_PLAYLIST_UNLIKED: u8 = 8;
+    pub const ACTIVITY_TRACK_TIPPED: u8 = 9;
+
+    /// Appends an entry, overwriting the oldest one once the feed is full.
+    /// Metadata is truncated to fit the fixed account size.
+    pub fn record(
+        &mut self,
+        user: Pubkey,
+        activity_type: u8,
+        target: Pubkey,
+        mut metadata: String,
+        created_at: i64,
+    ) {
+        if self.user == Pubkey::default() {
+            self.user = user;
+        }
+
+        if metadata.len() > ActivityEntry::MAX_METADATA_LEN {
+            let mut end = ActivityEntry::MAX_METADATA_LEN;
+            while !metadata.is_char_boundary(end) {
+                end -= 1;
+            }
+            metadata.truncate(end);
+        }
+
+        let entry = ActivityEntry {
+            activity_type,
+            target,
+            metadata,
+            created_at,
+        };
+
+        let slot = (self.head % Self::CAPACITY as u64) as usize;
+        if slot < self.entries.len() {
+            self.entries[slot] = entry;
+        } else {
+            self.entries.push(entry);
+        }
+        self.head = self.head.wrapping_add(1);
+    }
 }
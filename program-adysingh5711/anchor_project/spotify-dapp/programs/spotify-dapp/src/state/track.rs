@@ -13,10 +13,18 @@ pub struct Track {
     pub plays_count: u64,         // Number of plays (8 bytes)
     pub created_by: Pubkey,       // Creator of the track (32 bytes)
     pub created_at: i64,          // Timestamp (8 bytes)
+    pub tips_received: u64,       // Lamports tipped to the creator (8 bytes)
+    pub price_lamports: u64,      // Price per play, 0 for free tracks (8 bytes)
+    pub is_premium: bool,         // Requires an active subscription (1 byte)
+    pub album_key: Option<Pubkey>, // Album account the track belongs to (1 + 32 = 33 bytes)
+    pub artist_profile: Option<Pubkey>, // Artist account of the creator (1 + 32 = 33 bytes)
+    pub playlists_count: u64,     // Number of playlists containing the track (8 bytes)
+    pub search_entries_count: u64, // Number of search index accounts pointing here (8 bytes)
+    pub listeners_count: u64,     // Number of TrackPlay records, one per listener (8 bytes)
 }
 
 impl Track {
-    pub const MAX_SIZE: usize = 8 + 132 + 68 + 68 + 36 + 8 + 260 + 260 + 8 + 8 + 32 + 8; // 896 bytes
+    pub const MAX_SIZE: usize = 8 + 132 + 68 + 68 + 36 + 8 + 260 + 260 + 8 + 8 + 32 + 8 + 8 + 8 + 1 + 33 + 33 + 8 + 8 + 8; // 1003 bytes
 }
 
 #[account]
@@ -31,4 +39,21 @@ pub struct TrackPlay {
 
 impl TrackPlay {
     pub const MAX_SIZE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8; // 104 bytes
+
+    // Seconds that must be listened before a play counts (shorter tracks must be played fully)
+    pub const MIN_PLAY_SECONDS: u64 = 30;
+    // Minimum seconds between two counted plays of the same track by the same user
+    pub const COOLDOWN_SECONDS: i64 = 60;
+
+    pub fn min_play_duration(track_duration: u64) -> u64 {
+        track_duration.min(Self::MIN_PLAY_SECONDS)
+    }
+
+    /// A new play of `duration_played` seconds must not overlap the previous one
+    /// and must respect the cooldown.
+    pub fn is_cooling_down(&self, now: i64, duration_played: u64) -> bool {
+        let elapsed = now.saturating_sub(self.last_played_at);
+        let required = Self::COOLDOWN_SECONDS.max(i64::try_from(duration_played).unwrap_or(i64::MAX));
+        elapsed < required
+    }
 }
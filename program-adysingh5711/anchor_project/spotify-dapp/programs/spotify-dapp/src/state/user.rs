@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::errors::SpotifyError;
 
 #[account]
 pub struct UserProfile {
@@ -10,10 +11,11 @@ pub struct UserProfile {
     pub followers_count: u64,     // Number of followers (8 bytes)
     pub following_count: u64,     // Number of following (8 bytes)
     pub created_at: i64,          // Timestamp (8 bytes)
+    pub search_entries_count: u64, // Number of search index accounts pointing here (8 bytes)
 }
 
 impl UserProfile {
-    pub const MAX_SIZE: usize = 8 + 32 + 36 + 68 + 260 + 260 + 8 + 8 + 8; // 688 bytes
+    pub const MAX_SIZE: usize = 8 + 32 + 36 + 68 + 260 + 260 + 8 + 8 + 8 + 8; // 696 bytes
 }
 
 #[account]
@@ -24,8 +26,32 @@ pub struct UserStats {
     pub total_likes_received: u64, // Total likes on user's content (8 bytes)
     pub total_plays: u64,         // Total plays across all tracks (8 bytes)
     pub last_active: i64,         // Last activity timestamp (8 bytes)
+    pub total_tips_received: u64, // Lamports tipped across all tracks (8 bytes)
 }
 
 impl UserStats {
-    pub const MAX_SIZE: usize = 8 + 32 + 8 + 8 + 8 + 8 + 8; // 80 bytes
+    pub const MAX_SIZE: usize = 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8; // 88 bytes
+}
+
+#[account]
+pub struct UsernameClaim {
+    pub profile: Pubkey,          // User profile owning the name (32 bytes)
+    pub authority: Pubkey,        // User's wallet (32 bytes)
+    pub username: String,         // Normalised username (4 + 32 = 36 bytes)
+    pub created_at: i64,          // Timestamp (8 bytes)
+}
+
+impl UsernameClaim {
+    pub const MAX_SIZE: usize = 8 + 32 + 32 + 36 + 8; // 116 bytes
+
+    pub const MAX_USERNAME_LEN: usize = 32;
+
+    /// Usernames are unique case-insensitively, so claims are keyed on the lowercased name.
+    /// Lowercasing can lengthen a name, so the result is checked against the stored field
+    /// and the 32-byte seed limit before it is used as a seed.
+    pub fn normalize(username: &str) -> Result<String> {
+        let normalized = username.to_lowercase();
+        require!(normalized.len() <= Self::MAX_USERNAME_LEN, SpotifyError::UsernameTooLong);
+        Ok(normalized)
+    }
 }
@@ -3,6 +3,8 @@ import { Program } from "@coral-xyz/anchor";
 import { SpotifyDapp } from "../target/types/spotify_dapp";
 import { PublicKey, Keypair } from "@solana/web3.js";
 import { expect } from "chai";
+import { createHash } from "crypto";
+import { readFileSync } from "fs";
 
 describe("spotify_dapp", () => {
   // Configure the client to use the local cluster.
@@ -15,6 +17,61 @@ describe("spotify_dapp", () => {
   const userKeypair = Keypair.generate();
   const secondUserKeypair = Keypair.generate();
 
+  const searchIndexPda = (term: string, target: PublicKey): PublicKey =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("search_index"), Buffer.from(term), target.toBuffer()],
+      program.programId
+    )[0];
+
+  // Mirrors SearchIndex::tokenize: words first, then 2..6 character prefixes, 16 tokens max
+  const searchTokens = (...texts: string[]): string[] => {
+    const words: string[] = [];
+    for (const text of texts) {
+      for (const raw of text.split(/[^\p{L}\p{N}]+/u).filter((w) => w.length > 0)) {
+        let word = raw.toLowerCase();
+        while (Buffer.byteLength(word) > 32) word = word.slice(0, -1);
+        if (!words.includes(word)) words.push(word);
+      }
+    }
+    const tokens = [...words];
+    for (const word of words) {
+      const chars = Array.from(word);
+      for (let len = 2; len <= Math.min(6, chars.length - 1); len++) {
+        const prefix = chars.slice(0, len).join("");
+        if (!tokens.includes(prefix)) tokens.push(prefix);
+      }
+    }
+    return tokens.slice(0, 16);
+  };
+
+  const searchIndexAccounts = (target: PublicKey, terms: string[]) =>
+    terms.map((term) => ({
+      pubkey: searchIndexPda(term, target),
+      isWritable: true,
+      isSigner: false,
+    }));
+
+  // Mirrors ArtistNameClaim::seed
+  const artistNameClaimPda = (name: string): PublicKey =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("artist_name"), createHash("sha256").update(name.toLowerCase()).digest()],
+      program.programId
+    )[0];
+
+  // Mirrors UsernameClaim::normalize
+  const usernameClaimPda = (username: string): PublicKey =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("username"), Buffer.from(username.toLowerCase())],
+      program.programId
+    )[0];
+
+  const [configPda] = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId);
+
+  const [programDataPda] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+  );
+
   before(async () => {
     // Airdrop SOL to test users
     const airdropTx1 = await provider.connection.requestAirdrop(
@@ -46,14 +103,17 @@ describe("spotify_dapp", () => {
         .createUserProfile(username, displayName, bio, profileImage)
         .accounts({
           userProfile: userProfilePda,
+          usernameClaim: usernameClaimPda(username),
           authority: userKeypair.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
+        .remainingAccounts(searchIndexAccounts(userProfilePda, searchTokens(username, displayName)))
         .signers([userKeypair])
         .rpc();
 
       // Fetch and verify the created account
       const userProfile = await program.account.userProfile.fetch(userProfilePda);
+      expect(userProfile.searchEntriesCount.toNumber()).to.equal(searchTokens(username, displayName).length);
       expect(userProfile.authority.toString()).to.equal(userKeypair.publicKey.toString());
       expect(userProfile.username).to.equal(username);
       expect(userProfile.displayName).to.equal(displayName);
@@ -80,6 +140,7 @@ describe("spotify_dapp", () => {
           .createUserProfile(username, displayName, bio, profileImage)
           .accounts({
             userProfile: userProfilePda,
+            usernameClaim: usernameClaimPda(username),
             authority: secondUserKeypair.publicKey,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
@@ -90,6 +151,125 @@ describe("spotify_dapp", () => {
         expect(error.error.errorMessage).to.include("Username cannot be empty");
       }
     });
+
+    it("Should fail to create user profile whose lowercased username is too long", async () => {
+      // "İ" is two bytes but lowercases to three, so the claim seed would not fit
+      const username = "İ".repeat(16);
+      const displayName = "Long Lowercase";
+
+      const [userProfilePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_profile"), secondUserKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .createUserProfile(username, displayName, "", "")
+          .accounts({
+            userProfile: userProfilePda,
+            usernameClaim: PublicKey.findProgramAddressSync(
+              [Buffer.from("username"), Buffer.from(username.toLowerCase()).subarray(0, 32)],
+              program.programId
+            )[0],
+            authority: secondUserKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([secondUserKeypair])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.error.errorMessage).to.include("Username cannot be longer than 32 characters");
+      }
+    });
+  });
+
+  describe("Username Claim Tests", () => {
+    const claimantKeypair = Keypair.generate();
+    const newcomerKeypair = Keypair.generate();
+
+    const profilePda = (owner: Keypair) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("user_profile"), owner.publicKey.toBuffer()],
+        program.programId
+      )[0];
+
+    const createProfile = (owner: Keypair, username: string) =>
+      program.methods
+        .createUserProfile(username, username, "", "")
+        .accounts({
+          userProfile: profilePda(owner),
+          usernameClaim: usernameClaimPda(username),
+          authority: owner.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+
+    const changeUsername = (oldUsername: string, newUsername: string) =>
+      program.methods
+        .changeUsername(newUsername)
+        .accounts({
+          userProfile: profilePda(claimantKeypair),
+          oldUsernameClaim: usernameClaimPda(oldUsername),
+          newUsernameClaim: usernameClaimPda(newUsername),
+          authority: claimantKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([claimantKeypair])
+        .rpc();
+
+    before(async () => {
+      for (const keypair of [claimantKeypair, newcomerKeypair]) {
+        const airdropTx = await provider.connection.requestAirdrop(
+          keypair.publicKey,
+          anchor.web3.LAMPORTS_PER_SOL
+        );
+        await provider.connection.confirmTransaction(airdropTx);
+      }
+    });
+
+    it("Should reject a username taken with different case", async () => {
+      try {
+        await createProfile(claimantKeypair, "TestUser");
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.logs.join("\n")).to.include("already in use");
+      }
+    });
+
+    it("Should change only the case of a username", async () => {
+      await createProfile(claimantKeypair, "claimant");
+      await changeUsername("claimant", "Claimant");
+
+      const userProfile = await program.account.userProfile.fetch(profilePda(claimantKeypair));
+      expect(userProfile.username).to.equal("Claimant");
+
+      const claim = await program.account.usernameClaim.fetch(usernameClaimPda("claimant"));
+      expect(claim.profile.toString()).to.equal(profilePda(claimantKeypair).toString());
+      expect(claim.username).to.equal("claimant");
+    });
+
+    it("Should reject changing to a taken username", async () => {
+      try {
+        await changeUsername("Claimant", "TESTUSER");
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.error.errorMessage).to.include("Username is already taken");
+      }
+    });
+
+    it("Should release the old username on change", async () => {
+      await changeUsername("Claimant", "renamed");
+
+      expect(await provider.connection.getAccountInfo(usernameClaimPda("claimant"))).to.be.null;
+      const claim = await program.account.usernameClaim.fetch(usernameClaimPda("renamed"));
+      expect(claim.profile.toString()).to.equal(profilePda(claimantKeypair).toString());
+
+      // Another wallet can now take the released name
+      await createProfile(newcomerKeypair, "Claimant");
+      const newcomerClaim = await program.account.usernameClaim.fetch(usernameClaimPda("Claimant"));
+      expect(newcomerClaim.profile.toString()).to.equal(profilePda(newcomerKeypair).toString());
+    });
   });
 
   describe("Playlist Tests", () => {
@@ -132,6 +312,7 @@ describe("spotify_dapp", () => {
       const title = "Test Song";
       const artist = "Test Artist";
       const album = "Test Album";
+      const genre = "Pop";
       const duration = 180; // 3 minutes
       const audioUrl = "https://example.com/audio.mp3";
       const coverImage = "https://example.com/cover.jpg";
@@ -142,9 +323,14 @@ describe("spotify_dapp", () => {
       );
 
       await program.methods
-        .createTrack(title, artist, album, new anchor.BN(duration), audioUrl, coverImage)
+        .createTrack(title, artist, album, genre, new anchor.BN(duration), audioUrl, coverImage, new anchor.BN(0), false)
         .accounts({
           track: trackPda,
+          artistProfile: null,
+          artistNameClaim: artistNameClaimPda(artist),
+          creatorEscrow: null,
+          albumAccount: null,
+          albumTrack: null,
           authority: userKeypair.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
@@ -267,6 +453,39 @@ describe("spotify_dapp", () => {
       expect(track.likesCount.toNumber()).to.equal(0);
     });
 
+    it("Should fail to unlike a track that is not liked", async () => {
+      const title = "Test Song";
+      const artist = "Test Artist";
+
+      const [trackPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("track"), Buffer.from(title), Buffer.from(artist)],
+        program.programId
+      );
+
+      const [trackLikePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("track_like"), userKeypair.publicKey.toBuffer(), trackPda.toBuffer()],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .unlikeTrack()
+          .accounts({
+            track: trackPda,
+            trackLike: trackLikePda,
+            user: userKeypair.publicKey,
+          })
+          .signers([userKeypair])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.error.errorMessage).to.include("Track not liked");
+      }
+
+      const track = await program.account.track.fetch(trackPda);
+      expect(track.likesCount.toNumber()).to.equal(0);
+    });
+
     it("Should like a playlist successfully", async () => {
       const playlistName = "My Test Playlist";
 
@@ -300,6 +519,64 @@ describe("spotify_dapp", () => {
       const playlist = await program.account.playlist.fetch(playlistPda);
       expect(playlist.likesCount.toNumber()).to.equal(1);
     });
+
+    it("Should unlike a playlist successfully", async () => {
+      const playlistName = "My Test Playlist";
+
+      const [playlistPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("playlist"), userKeypair.publicKey.toBuffer(), Buffer.from(playlistName)],
+        program.programId
+      );
+
+      const [playlistLikePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("playlist_like"), userKeypair.publicKey.toBuffer(), playlistPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .unlikePlaylist()
+        .accounts({
+          playlist: playlistPda,
+          playlistLike: playlistLikePda,
+          user: userKeypair.publicKey,
+        })
+        .signers([userKeypair])
+        .rpc();
+
+      // Verify the like was closed and the count decreased
+      expect(await provider.connection.getAccountInfo(playlistLikePda)).to.be.null;
+      const playlist = await program.account.playlist.fetch(playlistPda);
+      expect(playlist.likesCount.toNumber()).to.equal(0);
+    });
+
+    it("Should fail to unlike a playlist that is not liked", async () => {
+      const playlistName = "My Test Playlist";
+
+      const [playlistPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("playlist"), userKeypair.publicKey.toBuffer(), Buffer.from(playlistName)],
+        program.programId
+      );
+
+      const [playlistLikePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("playlist_like"), userKeypair.publicKey.toBuffer(), playlistPda.toBuffer()],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .unlikePlaylist()
+          .accounts({
+            playlist: playlistPda,
+            playlistLike: playlistLikePda,
+            user: userKeypair.publicKey,
+          })
+          .signers([userKeypair])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.error.errorMessage).to.include("Playlist not liked");
+      }
+    });
   });
 
   describe("Phase 2: User Following Tests", () => {
@@ -318,6 +595,7 @@ describe("spotify_dapp", () => {
         .createUserProfile(username, displayName, bio, profileImage)
         .accounts({
           userProfile: userProfilePda,
+          usernameClaim: usernameClaimPda(username),
           authority: secondUserKeypair.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
@@ -403,6 +681,39 @@ describe("spotify_dapp", () => {
       expect(followerProfile.followingCount.toNumber()).to.equal(0);
       expect(followingProfile.followersCount.toNumber()).to.equal(0);
     });
+
+    it("Should fail to unfollow a user that is not followed", async () => {
+      const [followerProfilePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_profile"), userKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const [followingProfilePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_profile"), secondUserKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const [userFollowPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_follow"), userKeypair.publicKey.toBuffer(), followingProfilePda.toBuffer()],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .unfollowUser()
+          .accounts({
+            followerProfile: followerProfilePda,
+            followingProfile: followingProfilePda,
+            userFollow: userFollowPda,
+            follower: userKeypair.publicKey,
+          })
+          .signers([userKeypair])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.error.errorMessage).to.include("Not following this user");
+      }
+    });
   });
 
   describe("Phase 2: Playlist Management Tests", () => {
@@ -416,7 +727,7 @@ describe("spotify_dapp", () => {
       );
 
       await program.methods
-        .updatePlaylist(null, newDescription, false)
+        .updatePlaylist(newDescription, false, null)
         .accounts({
           playlist: playlistPda,
           authority: userKeypair.publicKey,
@@ -456,6 +767,7 @@ describe("spotify_dapp", () => {
           playlist: playlistPda,
           playlistTrack: playlistTrackPda,
           track: trackPda,
+          addedBy: userKeypair.publicKey,
           authority: userKeypair.publicKey,
         })
         .signers([userKeypair])
@@ -467,117 +779,758 @@ describe("spotify_dapp", () => {
     });
   });
 
-  describe("Phase 3: Enhanced Features Tests", () => {
-    describe("Analytics and Insights", () => {
-      it("Should generate user insights successfully", async () => {
-        const [userInsightsPda] = PublicKey.findProgramAddressSync(
-          [Buffer.from("user_insights"), userKeypair.publicKey.toBuffer()],
-          program.programId
-        );
+  describe("Phase 2: Playlist Ordering Tests", () => {
+    const playlistName = "Ordering Playlist";
+    const artist = "Ordering Artist";
+    const titles = ["First Cut", "Second Cut", "Third Cut"];
 
-        const [userStatsPda] = PublicKey.findProgramAddressSync(
-          [Buffer.from("user_stats"), userKeypair.publicKey.toBuffer()],
-          program.programId
-        );
+    let playlistPda: PublicKey;
+    let trackPdas: PublicKey[];
+    let entryPdas: PublicKey[];
 
-        await program.methods
-          .generateUserInsights()
-          .accounts({
-            userInsights: userInsightsPda,
-            userStats: userStatsPda,
-            user: userKeypair.publicKey,
-            systemProgram: anchor.web3.SystemProgram.programId,
-          })
-          .signers([userKeypair])
-          .rpc();
+    const writable = (...keys: PublicKey[]) =>
+      keys.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }));
 
-        // Verify insights were generated
-        const userInsights = await program.account.userInsights.fetch(userInsightsPda);
-        expect(userInsights.user.toString()).to.equal(userKeypair.publicKey.toString());
-        expect(userInsights.generatedAt.toNumber()).to.be.greaterThan(0);
-      });
+    const positions = async () =>
+      Promise.all(
+        entryPdas.map(async (entry) => (await program.account.playlistTrack.fetch(entry)).position.toNumber())
+      );
 
-      it("Should create recommendation successfully", async () => {
-        const title = "Test Song";
-        const artist = "Test Artist";
+    before(async () => {
+      [playlistPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("playlist"), userKeypair.publicKey.toBuffer(), Buffer.from(playlistName)],
+        program.programId
+      );
+
+      await program.methods
+        .createPlaylist(playlistName, "Tracks that get reordered", false, false)
+        .accounts({
+          playlist: playlistPda,
+          authority: userKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([userKeypair])
+        .rpc();
 
+      trackPdas = [];
+      entryPdas = [];
+      for (const title of titles) {
         const [trackPda] = PublicKey.findProgramAddressSync(
           [Buffer.from("track"), Buffer.from(title), Buffer.from(artist)],
           program.programId
         );
-
-        const recommendationType = 1; // Track recommendation
-        const score = 0.85;
-        const reason = "Based on your listening history";
-
-        const [recommendationPda] = PublicKey.findProgramAddressSync(
-          [
-            Buffer.from("recommendation"),
-            userKeypair.publicKey.toBuffer(),
-            trackPda.toBuffer(),
-            Buffer.from([recommendationType])
-          ],
+        const [entryPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("playlist_track"), playlistPda.toBuffer(), trackPda.toBuffer()],
           program.programId
         );
 
         await program.methods
-          .createRecommendation(recommendationType, trackPda, score, reason)
+          .createTrack(title, artist, "", "", new anchor.BN(120), "https://example.com/cut.mp3", "", new anchor.BN(0), false)
           .accounts({
-            recommendation: recommendationPda,
-            user: userKeypair.publicKey,
+            track: trackPda,
+            artistProfile: null,
+            artistNameClaim: artistNameClaimPda(artist),
+            creatorEscrow: null,
+            albumAccount: null,
+            albumTrack: null,
+            authority: userKeypair.publicKey,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
           .signers([userKeypair])
           .rpc();
 
-        // Verify recommendation was created
-        const recommendation = await program.account.recommendation.fetch(recommendationPda);
-        expect(recommendation.user.toString()).to.equal(userKeypair.publicKey.toString());
-        expect(recommendation.recommendationType).to.equal(recommendationType);
-        expect(recommendation.target.toString()).to.equal(trackPda.toString());
-        expect(recommendation.score).to.be.closeTo(score, 0.01);
-        expect(recommendation.reason).to.equal(reason);
-        expect(recommendation.isViewed).to.equal(false);
-      });
-    });
-
-    describe("Search Functionality", () => {
-      it("Should create search index successfully", async () => {
-        const searchTerm = "test song";
-        const targetType = 1; // Track type
-        const title = "Test Song";
-        const artist = "Test Artist";
-
-        const [trackPda] = PublicKey.findProgramAddressSync(
-          [Buffer.from("track"), Buffer.from(title), Buffer.from(artist)],
-          program.programId
-        );
-
-        const [searchIndexPda] = PublicKey.findProgramAddressSync(
-          [
-            Buffer.from("search_index"),
-            Buffer.from(searchTerm.toLowerCase()),
-            Buffer.from([targetType])
-          ],
-          program.programId
-        );
-
         await program.methods
-          .createSearchIndex(searchTerm, targetType, trackPda)
+          .addTrackToPlaylist()
           .accounts({
-            searchIndex: searchIndexPda,
+            playlist: playlistPda,
+            playlistTrack: entryPda,
+            track: trackPda,
             authority: userKeypair.publicKey,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
           .signers([userKeypair])
           .rpc();
 
-        // Verify search index was created
-        const searchIndex = await program.account.searchIndex.fetch(searchIndexPda);
-        expect(searchIndex.searchTerm).to.equal(searchTerm.toLowerCase());
-        expect(searchIndex.targetType).to.equal(targetType);
-        expect(searchIndex.targetPubkey.toString()).to.equal(trackPda.toString());
-        expect(searchIndex.createdAt.toNumber()).to.be.greaterThan(0);
+        trackPdas.push(trackPda);
+        entryPdas.push(entryPda);
+      }
+
+      expect(await positions()).to.deep.equal([0, 1, 2]);
+    });
+
+    it("Should reject a move that passes the same entry twice", async () => {
+      try {
+        await program.methods
+          .movePlaylistTrack(new anchor.BN(0))
+          .accounts({
+            playlist: playlistPda,
+            playlistTrack: entryPdas[2],
+            track: trackPdas[2],
+            authority: userKeypair.publicKey,
+          })
+          .remainingAccounts(writable(entryPdas[0], entryPdas[0]))
+          .signers([userKeypair])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.error.errorMessage).to.include("Playlist track accounts do not match");
+      }
+
+      expect(await positions()).to.deep.equal([0, 1, 2]);
+    });
+
+    it("Should move a track to the front of the playlist", async () => {
+      await program.methods
+        .movePlaylistTrack(new anchor.BN(0))
+        .accounts({
+          playlist: playlistPda,
+          playlistTrack: entryPdas[2],
+          track: trackPdas[2],
+          authority: userKeypair.publicKey,
+        })
+        .remainingAccounts(writable(entryPdas[0], entryPdas[1]))
+        .signers([userKeypair])
+        .rpc();
+
+      expect(await positions()).to.deep.equal([1, 2, 0]);
+    });
+
+    it("Should reject a removal that passes the same entry twice", async () => {
+      try {
+        await program.methods
+          .removeTrackFromPlaylist()
+          .accounts({
+            playlist: playlistPda,
+            playlistTrack: entryPdas[2],
+            track: trackPdas[2],
+            addedBy: userKeypair.publicKey,
+            authority: userKeypair.publicKey,
+          })
+          .remainingAccounts(writable(entryPdas[1], entryPdas[1]))
+          .signers([userKeypair])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.error.errorMessage).to.include("Playlist track accounts do not match");
+      }
+
+      expect(await positions()).to.deep.equal([1, 2, 0]);
+    });
+
+    it("Should remove a track and close the gap", async () => {
+      await program.methods
+        .removeTrackFromPlaylist()
+        .accounts({
+          playlist: playlistPda,
+          playlistTrack: entryPdas[2],
+          track: trackPdas[2],
+          addedBy: userKeypair.publicKey,
+          authority: userKeypair.publicKey,
+        })
+        .remainingAccounts(writable(entryPdas[0], entryPdas[1]))
+        .signers([userKeypair])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(entryPdas[2])).to.be.null;
+      entryPdas = entryPdas.slice(0, 2);
+      expect(await positions()).to.deep.equal([0, 1]);
+
+      const playlist = await program.account.playlist.fetch(playlistPda);
+      expect(playlist.tracksCount.toNumber()).to.equal(2);
+    });
+  });
+
+  describe("Phase 3: Enhanced Features Tests", () => {
+    const updateEnhancedTrack = (
+      signer: Keypair,
+      fields: {
+        album?: string;
+        genre?: string;
+        duration?: number;
+        audioUrl?: string;
+        coverImage?: string;
+      }
+    ) =>
+      program.methods
+        .updateTrack(
+          fields.album ?? null,
+          fields.genre ?? null,
+          fields.duration === undefined ? null : new anchor.BN(fields.duration),
+          fields.audioUrl ?? null,
+          fields.coverImage ?? null,
+          null,
+          null
+        )
+        .accounts({
+          track: PublicKey.findProgramAddressSync(
+            [Buffer.from("track"), Buffer.from("Enhanced Track"), Buffer.from("Enhanced Artist")],
+            program.programId
+          )[0],
+          userStats: PublicKey.findProgramAddressSync(
+            [Buffer.from("user_stats"), signer.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          creatorEscrow: null,
+          authority: signer.publicKey,
+        })
+        .signers([signer])
+        .rpc();
+
+    describe("Analytics and Insights", () => {
+      it("Should generate user insights successfully", async () => {
+        const [userInsightsPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("user_insights"), userKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+
+        const [userStatsPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("user_stats"), userKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+
+        await program.methods
+          .generateUserInsights()
+          .accounts({
+            userInsights: userInsightsPda,
+            userStats: userStatsPda,
+            user: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+        // Verify insights were generated
+        const userInsights = await program.account.userInsights.fetch(userInsightsPda);
+        expect(userInsights.user.toString()).to.equal(userKeypair.publicKey.toString());
+        expect(userInsights.generatedAt.toNumber()).to.be.greaterThan(0);
+      });
+
+      it("Should create recommendation successfully", async () => {
+        const title = "Test Song";
+        const artist = "Test Artist";
+
+        const [trackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track"), Buffer.from(title), Buffer.from(artist)],
+          program.programId
+        );
+
+        const recommendationType = 1; // Track recommendation
+        const score = 0.85;
+        const reason = "Based on your listening history";
+
+        const [recommendationPda] = PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("recommendation"),
+            userKeypair.publicKey.toBuffer(),
+            trackPda.toBuffer(),
+            Buffer.from([recommendationType])
+          ],
+          program.programId
+        );
+
+        await program.methods
+          .createRecommendation(recommendationType, trackPda, score, reason)
+          .accounts({
+            recommendation: recommendationPda,
+            targetAccount: trackPda,
+            user: userKeypair.publicKey,
+            payer: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+        // Verify recommendation was created
+        const recommendation = await program.account.recommendation.fetch(recommendationPda);
+        expect(recommendation.user.toString()).to.equal(userKeypair.publicKey.toString());
+        expect(recommendation.recommendationType).to.equal(recommendationType);
+        expect(recommendation.target.toString()).to.equal(trackPda.toString());
+        expect(recommendation.score).to.be.closeTo(score, 0.01);
+        expect(recommendation.reason).to.equal(reason);
+        expect(recommendation.isViewed).to.equal(false);
+      });
+
+      describe("Recommendation lifecycle", () => {
+        const [trackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track"), Buffer.from("Test Song"), Buffer.from("Test Artist")],
+          program.programId
+        );
+
+        const [playlistPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("playlist"), userKeypair.publicKey.toBuffer(), Buffer.from("My Test Playlist")],
+          program.programId
+        );
+
+        const recommendationPda = (user: PublicKey, target: PublicKey, recommendationType: number) =>
+          PublicKey.findProgramAddressSync(
+            [Buffer.from("recommendation"), user.toBuffer(), target.toBuffer(), Buffer.from([recommendationType])],
+            program.programId
+          )[0];
+
+        it("Should mark a recommendation as viewed", async () => {
+          const recommendation = recommendationPda(userKeypair.publicKey, trackPda, 1);
+
+          await program.methods
+            .markRecommendationViewed()
+            .accounts({
+              recommendation,
+              user: userKeypair.publicKey,
+            })
+            .signers([userKeypair])
+            .rpc();
+
+          const account = await program.account.recommendation.fetch(recommendation);
+          expect(account.isViewed).to.equal(true);
+        });
+
+        it("Should refuse to expire a fresh recommendation", async () => {
+          const recommendation = recommendationPda(userKeypair.publicKey, trackPda, 1);
+
+          try {
+            await program.methods
+              .expireRecommendation()
+              .accounts({
+                recommendation,
+                payer: userKeypair.publicKey,
+                caller: secondUserKeypair.publicKey,
+              })
+              .signers([secondUserKeypair])
+              .rpc();
+            expect.fail("Should have thrown an error");
+          } catch (error) {
+            expect(error.error.errorMessage).to.include("Recommendation has not expired yet");
+          }
+        });
+
+        it("Should dismiss a recommendation and refund its payer", async () => {
+          const recommendation = recommendationPda(userKeypair.publicKey, trackPda, 1);
+
+          await program.methods
+            .dismissRecommendation()
+            .accounts({
+              recommendation,
+              payer: userKeypair.publicKey,
+              user: userKeypair.publicKey,
+            })
+            .signers([userKeypair])
+            .rpc();
+
+          expect(await provider.connection.getAccountInfo(recommendation)).to.be.null;
+        });
+
+        it("Should reject an unknown recommendation type", async () => {
+          try {
+            await program.methods
+              .createRecommendation(9, trackPda, 0.5, "Unknown type")
+              .accounts({
+                recommendation: recommendationPda(userKeypair.publicKey, trackPda, 9),
+                targetAccount: trackPda,
+                user: userKeypair.publicKey,
+                config: null,
+                payer: userKeypair.publicKey,
+                systemProgram: anchor.web3.SystemProgram.programId,
+              })
+              .signers([userKeypair])
+              .rpc();
+            expect.fail("Should have thrown an error");
+          } catch (error) {
+            expect(error.error.errorMessage).to.include("Invalid recommendation type");
+          }
+        });
+
+        it("Should reject a target of the wrong type", async () => {
+          try {
+            await program.methods
+              .createRecommendation(2, trackPda, 0.5, "A track is not a playlist")
+              .accounts({
+                recommendation: recommendationPda(userKeypair.publicKey, trackPda, 2),
+                targetAccount: trackPda,
+                user: userKeypair.publicKey,
+                config: null,
+                payer: userKeypair.publicKey,
+                systemProgram: anchor.web3.SystemProgram.programId,
+              })
+              .signers([userKeypair])
+              .rpc();
+            expect.fail("Should have thrown an error");
+          } catch (error) {
+            expect(error.error.errorMessage).to.include("Recommendation target does not match");
+          }
+        });
+
+        it("Should reject a target account that differs from the target", async () => {
+          try {
+            await program.methods
+              .createRecommendation(1, trackPda, 0.5, "Mismatched target account")
+              .accounts({
+                recommendation: recommendationPda(userKeypair.publicKey, trackPda, 1),
+                targetAccount: playlistPda,
+                user: userKeypair.publicKey,
+                config: null,
+                payer: userKeypair.publicKey,
+                systemProgram: anchor.web3.SystemProgram.programId,
+              })
+              .signers([userKeypair])
+              .rpc();
+            expect.fail("Should have thrown an error");
+          } catch (error) {
+            expect(error.error.errorMessage).to.include("Recommendation target does not match");
+          }
+        });
+      });
+
+      describe("Recommenders", () => {
+        const recommenderKeypair = Keypair.generate();
+
+        const [targetProfilePda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("user_profile"), secondUserKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+
+        const [recommendationPda] = PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("recommendation"),
+            userKeypair.publicKey.toBuffer(),
+            targetProfilePda.toBuffer(),
+            Buffer.from([3])
+          ],
+          program.programId
+        );
+
+        const pushRecommendation = (payer: Keypair) =>
+          program.methods
+            .createRecommendation(3, targetProfilePda, 0.7, "People you may know")
+            .accounts({
+              recommendation: recommendationPda,
+              targetAccount: targetProfilePda,
+              user: userKeypair.publicKey,
+              config: configPda,
+              payer: payer.publicKey,
+              systemProgram: anchor.web3.SystemProgram.programId,
+            })
+            .signers([payer])
+            .rpc();
+
+        before(async () => {
+          const airdropTx = await provider.connection.requestAirdrop(
+            recommenderKeypair.publicKey,
+            anchor.web3.LAMPORTS_PER_SOL
+          );
+          await provider.connection.confirmTransaction(airdropTx);
+        });
+
+        it("Should initialize the program config", async () => {
+          await program.methods
+            .initializeConfig()
+            .accounts({
+              config: configPda,
+              program: program.programId,
+              programData: programDataPda,
+              authority: provider.wallet.publicKey,
+              systemProgram: anchor.web3.SystemProgram.programId,
+            })
+            .rpc();
+
+          await program.methods
+            .addRecommender(recommenderKeypair.publicKey)
+            .accounts({
+              config: configPda,
+              admin: provider.wallet.publicKey,
+            })
+            .rpc();
+
+          const config = await program.account.programConfig.fetch(configPda);
+          expect(config.admin.toString()).to.equal(provider.wallet.publicKey.toString());
+          expect(config.recommenders.map((key) => key.toString())).to.include(
+            recommenderKeypair.publicKey.toString()
+          );
+        });
+
+        it("Should reject recommendations pushed by an unregistered wallet", async () => {
+          try {
+            await pushRecommendation(secondUserKeypair);
+            expect.fail("Should have thrown an error");
+          } catch (error) {
+            expect(error.error.errorMessage).to.include("Unauthorized action");
+          }
+        });
+
+        it("Should let a recommender push a recommendation to another user", async () => {
+          await pushRecommendation(recommenderKeypair);
+
+          const recommendation = await program.account.recommendation.fetch(recommendationPda);
+          expect(recommendation.user.toString()).to.equal(userKeypair.publicKey.toString());
+          expect(recommendation.payer.toString()).to.equal(recommenderKeypair.publicKey.toString());
+          expect(recommendation.target.toString()).to.equal(targetProfilePda.toString());
+        });
+
+        it("Should refund the recommender when the user dismisses it", async () => {
+          const balanceBefore = await provider.connection.getBalance(recommenderKeypair.publicKey);
+
+          await program.methods
+            .dismissRecommendation()
+            .accounts({
+              recommendation: recommendationPda,
+              payer: recommenderKeypair.publicKey,
+              user: userKeypair.publicKey,
+            })
+            .signers([userKeypair])
+            .rpc();
+
+          const balanceAfter = await provider.connection.getBalance(recommenderKeypair.publicKey);
+          expect(balanceAfter).to.be.greaterThan(balanceBefore);
+          expect(await provider.connection.getAccountInfo(recommendationPda)).to.be.null;
+        });
+      });
+    });
+
+    describe("Search Functionality", () => {
+      it("Should create search index successfully", async () => {
+        const targetType = 1; // Track type
+        const title = "Test Song";
+        const artist = "Test Artist";
+
+        const [trackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track"), Buffer.from(title), Buffer.from(artist)],
+          program.programId
+        );
+
+        for (const searchTerm of ["song", "so"]) {
+          const indexPda = searchIndexPda(searchTerm, trackPda);
+
+          await program.methods
+            .createSearchIndex(searchTerm, targetType, trackPda)
+            .accounts({
+              searchIndex: indexPda,
+              targetAccount: trackPda,
+              authority: userKeypair.publicKey,
+              systemProgram: anchor.web3.SystemProgram.programId,
+            })
+            .signers([userKeypair])
+            .rpc();
+
+          // Verify search index was created
+          const searchIndex = await program.account.searchIndex.fetch(indexPda);
+          expect(searchIndex.searchTerm).to.equal(searchTerm);
+          expect(searchIndex.targetType).to.equal(targetType);
+          expect(searchIndex.targetPubkey.toString()).to.equal(trackPda.toString());
+          expect(searchIndex.creator.toString()).to.equal(userKeypair.publicKey.toString());
+          expect(searchIndex.createdAt.toNumber()).to.be.greaterThan(0);
+        }
+
+        const track = await program.account.track.fetch(trackPda);
+        expect(track.searchEntriesCount.toNumber()).to.equal(2);
+      });
+
+      it("Should index a track on create with the chosen terms", async () => {
+        const title = "Indexed Anthem";
+        const artist = "Search Artist";
+
+        const [trackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track"), Buffer.from(title), Buffer.from(artist)],
+          program.programId
+        );
+
+        // Whole words only, leaving the prefixes out
+        const terms = searchTokens(title, artist).slice(0, 4);
+        expect(terms).to.deep.equal(["indexed", "anthem", "search", "artist"]);
+
+        const createIndexedTrack = (chosen: string[]) =>
+          program.methods
+            .createTrack(title, artist, "", "", new anchor.BN(120), "https://example.com/anthem.mp3", "", new anchor.BN(0), false)
+            .accounts({
+              track: trackPda,
+              artistProfile: null,
+              artistNameClaim: artistNameClaimPda(artist),
+              creatorEscrow: null,
+              albumAccount: null,
+              albumTrack: null,
+              authority: userKeypair.publicKey,
+              systemProgram: anchor.web3.SystemProgram.programId,
+            })
+            .remainingAccounts(searchIndexAccounts(trackPda, chosen))
+            .signers([userKeypair])
+            .rpc();
+
+        // Terms must follow tokenize order
+        try {
+          await createIndexedTrack(["anthem", "indexed"]);
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("Search index accounts do not match the indexed terms");
+        }
+
+        await createIndexedTrack(terms);
+
+        for (const term of terms) {
+          const searchIndex = await program.account.searchIndex.fetch(searchIndexPda(term, trackPda));
+          expect(searchIndex.searchTerm).to.equal(term);
+          expect(searchIndex.targetPubkey.toString()).to.equal(trackPda.toString());
+          expect(searchIndex.creator.toString()).to.equal(userKeypair.publicKey.toString());
+        }
+        expect(await provider.connection.getAccountInfo(searchIndexPda("anth", trackPda))).to.be.null;
+
+        const track = await program.account.track.fetch(trackPda);
+        expect(track.searchEntriesCount.toNumber()).to.equal(terms.length);
+      });
+
+      it("Should close a playlist's search entries when it becomes private", async () => {
+        const playlistName = "Searchable Playlist";
+        const [playlistPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("playlist"), userKeypair.publicKey.toBuffer(), Buffer.from(playlistName)],
+          program.programId
+        );
+        const terms = ["searchable", "playlist"];
+
+        await program.methods
+          .createPlaylist(playlistName, "Found by name until it goes private", true, false)
+          .accounts({
+            playlist: playlistPda,
+            authority: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .remainingAccounts(searchIndexAccounts(playlistPda, terms))
+          .signers([userKeypair])
+          .rpc();
+
+        const makePrivate = (entries: string[]) =>
+          program.methods
+            .updatePlaylist(null, false, null)
+            .accounts({
+              playlist: playlistPda,
+              playlistCollaborator: null,
+              authority: userKeypair.publicKey,
+            })
+            .remainingAccounts(searchIndexAccounts(playlistPda, entries))
+            .signers([userKeypair])
+            .rpc();
+
+        try {
+          await makePrivate([]);
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("Search index accounts do not match the indexed terms");
+        }
+
+        await makePrivate(terms);
+
+        for (const term of terms) {
+          expect(await provider.connection.getAccountInfo(searchIndexPda(term, playlistPda))).to.be.null;
+        }
+        const playlist = await program.account.playlist.fetch(playlistPda);
+        expect(playlist.isPublic).to.be.false;
+        expect(playlist.searchEntriesCount.toNumber()).to.equal(0);
+      });
+
+      it("Should close search entries of terms that leave the display name", async () => {
+        const [userProfilePda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("user_profile"), userKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+        const oldTerms = searchTokens("testuser", "Test User");
+        const newTerms = searchTokens("testuser", "Test Listener");
+
+        await program.methods
+          .updateUserProfile("Test Listener", null, null)
+          .accounts({
+            userProfile: userProfilePda,
+            authority: userKeypair.publicKey,
+          })
+          .remainingAccounts(searchIndexAccounts(userProfilePda, oldTerms))
+          .signers([userKeypair])
+          .rpc();
+
+        const kept = oldTerms.filter((term) => newTerms.includes(term));
+        expect(kept).to.include("testuser");
+        expect(kept).to.not.include("user");
+        for (const term of oldTerms) {
+          const info = await provider.connection.getAccountInfo(searchIndexPda(term, userProfilePda));
+          if (kept.includes(term)) {
+            expect(info).to.not.be.null;
+          } else {
+            expect(info).to.be.null;
+          }
+        }
+
+        const userProfile = await program.account.userProfile.fetch(userProfilePda);
+        expect(userProfile.displayName).to.equal("Test Listener");
+        expect(userProfile.searchEntriesCount.toNumber()).to.equal(kept.length);
+      });
+
+      it("Should fail to index a term that is not in the target", async () => {
+        const [trackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track"), Buffer.from("Test Song"), Buffer.from("Test Artist")],
+          program.programId
+        );
+
+        try {
+          await program.methods
+            .createSearchIndex("anthem", 1, trackPda)
+            .accounts({
+              searchIndex: searchIndexPda("anthem", trackPda),
+              targetAccount: trackPda,
+              authority: userKeypair.publicKey,
+              systemProgram: anchor.web3.SystemProgram.programId,
+            })
+            .signers([userKeypair])
+            .rpc();
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("Search term does not appear in the target's searchable text");
+        }
+      });
+
+      it("Should move a search index entry to another term", async () => {
+        const [trackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track"), Buffer.from("Test Song"), Buffer.from("Test Artist")],
+          program.programId
+        );
+        const oldIndexPda = searchIndexPda("so", trackPda);
+        const newIndexPda = searchIndexPda("art", trackPda);
+
+        await program.methods
+          .updateSearchIndex("art")
+          .accounts({
+            oldSearchIndex: oldIndexPda,
+            newSearchIndex: newIndexPda,
+            targetAccount: trackPda,
+            authority: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+        expect(await provider.connection.getAccountInfo(oldIndexPda)).to.be.null;
+        const searchIndex = await program.account.searchIndex.fetch(newIndexPda);
+        expect(searchIndex.searchTerm).to.equal("art");
+        expect(searchIndex.targetPubkey.toString()).to.equal(trackPda.toString());
+
+        const track = await program.account.track.fetch(trackPda);
+        expect(track.searchEntriesCount.toNumber()).to.equal(2);
+      });
+
+      it("Should remove a search index entry", async () => {
+        const [trackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track"), Buffer.from("Test Song"), Buffer.from("Test Artist")],
+          program.programId
+        );
+        const indexPda = searchIndexPda("song", trackPda);
+
+        await program.methods
+          .removeSearchIndex()
+          .accounts({
+            searchIndex: indexPda,
+            targetAccount: trackPda,
+            authority: userKeypair.publicKey,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+        const info = await provider.connection.getAccountInfo(indexPda);
+        expect(info).to.be.null;
+
+        const track = await program.account.track.fetch(trackPda);
+        expect(track.searchEntriesCount.toNumber()).to.equal(1);
       });
     });
 
@@ -602,119 +1555,1303 @@ describe("spotify_dapp", () => {
           program.programId
         );
 
-        // Create a unique track play PDA with timestamp
-        const timestamp = Date.now();
-        const [trackPlayPda] = PublicKey.findProgramAddressSync(
+        const [trackPlayPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track_play"), trackPda.toBuffer(), userKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+
+        await program.methods
+          .playTrack(new anchor.BN(durationPlayed))
+          .accounts({
+            track: trackPda,
+            trackPlay: trackPlayPda,
+            userStats: userStatsPda,
+            creatorStats: creatorStatsPda,
+            user: userKeypair.publicKey,
+            creatorEscrow: null,
+            config: null,
+            feeRecipient: null,
+            subscription: null,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+        // Verify track play was recorded
+        const trackPlay = await program.account.trackPlay.fetch(trackPlayPda);
+        expect(trackPlay.track.toString()).to.equal(trackPda.toString());
+        expect(trackPlay.user.toString()).to.equal(userKeypair.publicKey.toString());
+        expect(trackPlay.playCount.toNumber()).to.equal(1);
+        expect(trackPlay.totalDuration.toNumber()).to.equal(durationPlayed);
+
+        // Verify track play count increased
+        const track = await program.account.track.fetch(trackPda);
+        expect(track.playsCount.toNumber()).to.equal(1);
+      });
+
+      it("Should reject plays that are too short or too frequent", async () => {
+        const [trackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track"), Buffer.from("Test Song"), Buffer.from("Test Artist")],
+          program.programId
+        );
+        const [trackPlayPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track_play"), trackPda.toBuffer(), userKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+        const [userStatsPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("user_stats"), userKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+
+        const play = (durationPlayed: number) =>
+          program.methods
+            .playTrack(new anchor.BN(durationPlayed))
+            .accounts({
+              track: trackPda,
+              trackPlay: trackPlayPda,
+              userStats: userStatsPda,
+              creatorStats: userStatsPda,
+              user: userKeypair.publicKey,
+              creatorEscrow: null,
+              config: null,
+              feeRecipient: null,
+              subscription: null,
+              systemProgram: anchor.web3.SystemProgram.programId,
+            })
+            .signers([userKeypair])
+            .rpc();
+
+        for (const [durationPlayed, error] of [
+          [5, "PlayTooShort"],
+          [10_000, "PlayDurationExceedsTrack"],
+          [120, "PlayCooldownActive"],
+        ] as const) {
+          try {
+            await play(durationPlayed);
+            expect.fail(`play of ${durationPlayed}s should have failed`);
+          } catch (err) {
+            expect(err.error.errorCode.code).to.equal(error);
+          }
+        }
+      });
+
+      it("Should tip the track creator", async () => {
+        const amount = 0.1 * anchor.web3.LAMPORTS_PER_SOL;
+        const [trackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track"), Buffer.from("Test Song"), Buffer.from("Test Artist")],
+          program.programId
+        );
+        const [creatorStatsPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("user_stats"), userKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+
+        const balanceBefore = await provider.connection.getBalance(userKeypair.publicKey);
+
+        await program.methods
+          .tipTrack(new anchor.BN(amount))
+          .accounts({
+            track: trackPda,
+            creatorStats: creatorStatsPda,
+            creator: userKeypair.publicKey,
+            tipper: secondUserKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([secondUserKeypair])
+          .rpc();
+
+        const balanceAfter = await provider.connection.getBalance(userKeypair.publicKey);
+        expect(balanceAfter - balanceBefore).to.equal(amount);
+
+        const track = await program.account.track.fetch(trackPda);
+        expect(track.tipsReceived.toNumber()).to.equal(amount);
+        const creatorStats = await program.account.userStats.fetch(creatorStatsPda);
+        expect(creatorStats.totalTipsReceived.toNumber()).to.equal(amount);
+      });
+
+      it("Should create enhanced track with genre", async () => {
+        const title = "Enhanced Track";
+        const artist = "Enhanced Artist";
+        const album = "Enhanced Album";
+        const genre = "Electronic";
+        const duration = 240;
+        const audioUrl = "https://example.com/enhanced.mp3";
+        const coverImage = "https://example.com/enhanced-cover.jpg";
+
+        const [trackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track"), Buffer.from(title), Buffer.from(artist)],
+          program.programId
+        );
+
+        const [userStatsPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("user_stats"), userKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+
+        await program.methods
+          .createTrack(title, artist, album, genre, new anchor.BN(duration), audioUrl, coverImage, new anchor.BN(0), false)
+          .accounts({
+            track: trackPda,
+            userStats: userStatsPda,
+            artistProfile: null,
+            artistNameClaim: artistNameClaimPda(artist),
+            creatorEscrow: null,
+            albumAccount: null,
+            albumTrack: null,
+            authority: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+        // Verify enhanced track was created
+        const track = await program.account.track.fetch(trackPda);
+        expect(track.title).to.equal(title);
+        expect(track.artist).to.equal(artist);
+        expect(track.genre).to.equal(genre);
+        expect(track.createdBy.toString()).to.equal(userKeypair.publicKey.toString());
+      });
+
+      it("Should update a track", async () => {
+        await updateEnhancedTrack(userKeypair, {
+          album: "Enhanced Remixes",
+          genre: "House",
+          duration: 250,
+          audioUrl: "https://example.com/enhanced-v2.mp3",
+          coverImage: "https://example.com/enhanced-cover-v2.jpg",
+        });
+
+        const [trackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track"), Buffer.from("Enhanced Track"), Buffer.from("Enhanced Artist")],
+          program.programId
+        );
+        const track = await program.account.track.fetch(trackPda);
+        expect(track.album).to.equal("Enhanced Remixes");
+        expect(track.genre).to.equal("House");
+        expect(track.duration.toNumber()).to.equal(250);
+        expect(track.audioUrl).to.equal("https://example.com/enhanced-v2.mp3");
+        expect(track.coverImage).to.equal("https://example.com/enhanced-cover-v2.jpg");
+      });
+
+      it("Should only let the creator update a track", async () => {
+        try {
+          await updateEnhancedTrack(secondUserKeypair, { genre: "Pop" });
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("Unauthorized action");
+        }
+      });
+
+      it("Should reject invalid track updates", async () => {
+        for (const [fields, message] of [
+          [{ album: "a".repeat(65) }, "Album name cannot be longer than 64 characters"],
+          [{ genre: "g".repeat(33) }, "Genre cannot be longer than 32 characters"],
+          [{ duration: 0 }, "Duration must be greater than 0"],
+          [{ audioUrl: "u".repeat(257) }, "Audio URL cannot be longer than 256 characters"],
+          [{ coverImage: "c".repeat(257) }, "Cover image URL cannot be longer than 256 characters"],
+        ] as const) {
+          try {
+            await updateEnhancedTrack(userKeypair, fields);
+            expect.fail(`update with ${Object.keys(fields)[0]} should have failed`);
+          } catch (error) {
+            expect(error.error.errorMessage).to.include(message);
+          }
+        }
+      });
+
+      it("Should refuse to delete a track that is still in a playlist", async () => {
+        const [trackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track"), Buffer.from("Test Song"), Buffer.from("Test Artist")],
+          program.programId
+        );
+
+        const track = await program.account.track.fetch(trackPda);
+        expect(track.playlistsCount.toNumber()).to.be.greaterThan(0);
+
+        try {
+          await program.methods
+            .deleteTrack()
+            .accounts({
+              track: trackPda,
+              authority: userKeypair.publicKey,
+            })
+            .signers([userKeypair])
+            .rpc();
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("Track is still in playlists");
+        }
+      });
+
+      it("Should delete an unused track", async () => {
+        const title = "Short Lived";
+        const artist = "Enhanced Artist";
+
+        const [trackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track"), Buffer.from(title), Buffer.from(artist)],
+          program.programId
+        );
+
+        await program.methods
+          .createTrack(title, artist, "", "", new anchor.BN(60), "https://example.com/short.mp3", "", new anchor.BN(0), false)
+          .accounts({
+            track: trackPda,
+            artistProfile: null,
+            artistNameClaim: artistNameClaimPda(artist),
+            creatorEscrow: null,
+            albumAccount: null,
+            albumTrack: null,
+            authority: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+        const indexPda = searchIndexPda("short", trackPda);
+        await program.methods
+          .createSearchIndex("short", 1, trackPda)
+          .accounts({
+            searchIndex: indexPda,
+            targetAccount: trackPda,
+            authority: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+        // The search entry must be closed together with the track
+        try {
+          await program.methods
+            .deleteTrack()
+            .accounts({
+              track: trackPda,
+              authority: userKeypair.publicKey,
+            })
+            .signers([userKeypair])
+            .rpc();
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("Search index accounts do not match the indexed terms");
+        }
+
+        await program.methods
+          .deleteTrack()
+          .accounts({
+            track: trackPda,
+            authority: userKeypair.publicKey,
+          })
+          .remainingAccounts([{ pubkey: indexPda, isWritable: true, isSigner: false }])
+          .signers([userKeypair])
+          .rpc();
+
+        expect(await provider.connection.getAccountInfo(trackPda)).to.be.null;
+        expect(await provider.connection.getAccountInfo(indexPda)).to.be.null;
+      });
+
+      it("Should delete a liked and played track, refunding the liker and listener", async () => {
+        const title = "Crowd Pleaser";
+        const artist = "Enhanced Artist";
+
+        const [trackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track"), Buffer.from(title), Buffer.from(artist)],
+          program.programId
+        );
+        const [trackLikePda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track_like"), secondUserKeypair.publicKey.toBuffer(), trackPda.toBuffer()],
+          program.programId
+        );
+        const [trackPlayPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track_play"), trackPda.toBuffer(), secondUserKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+        const [listenerStatsPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("user_stats"), secondUserKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+        const [creatorStatsPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("user_stats"), userKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+
+        await program.methods
+          .createTrack(title, artist, "", "", new anchor.BN(60), "https://example.com/crowd.mp3", "", new anchor.BN(0), false)
+          .accounts({
+            track: trackPda,
+            artistProfile: null,
+            artistNameClaim: artistNameClaimPda(artist),
+            creatorEscrow: null,
+            albumAccount: null,
+            albumTrack: null,
+            authority: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+        await program.methods
+          .likeTrack()
+          .accounts({
+            track: trackPda,
+            trackLike: trackLikePda,
+            user: secondUserKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([secondUserKeypair])
+          .rpc();
+
+        await program.methods
+          .playTrack(new anchor.BN(60))
+          .accounts({
+            track: trackPda,
+            trackPlay: trackPlayPda,
+            userStats: listenerStatsPda,
+            creatorStats: creatorStatsPda,
+            user: secondUserKeypair.publicKey,
+            creatorEscrow: null,
+            config: null,
+            feeRecipient: null,
+            subscription: null,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([secondUserKeypair])
+          .rpc();
+
+        const track = await program.account.track.fetch(trackPda);
+        expect(track.likesCount.toNumber()).to.equal(1);
+        expect(track.listenersCount.toNumber()).to.equal(1);
+        const likesBefore = (await program.account.userStats.fetch(creatorStatsPda)).totalLikesReceived.toNumber();
+
+        // The like and play records must be closed along with the track
+        try {
+          await program.methods
+            .deleteTrack()
+            .accounts({
+              track: trackPda,
+              authority: userKeypair.publicKey,
+            })
+            .signers([userKeypair])
+            .rpc();
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("likes and plays were not passed in to be closed");
+        }
+
+        const listenerBalanceBefore = await provider.connection.getBalance(secondUserKeypair.publicKey);
+
+        await program.methods
+          .deleteTrack()
+          .accounts({
+            track: trackPda,
+            authority: userKeypair.publicKey,
+          })
+          .remainingAccounts([
+            { pubkey: trackLikePda, isWritable: true, isSigner: false },
+            { pubkey: secondUserKeypair.publicKey, isWritable: true, isSigner: false },
+            { pubkey: trackPlayPda, isWritable: true, isSigner: false },
+            { pubkey: secondUserKeypair.publicKey, isWritable: true, isSigner: false },
+          ])
+          .signers([userKeypair])
+          .rpc();
+
+        expect(await provider.connection.getAccountInfo(trackPda)).to.be.null;
+        expect(await provider.connection.getAccountInfo(trackLikePda)).to.be.null;
+        expect(await provider.connection.getAccountInfo(trackPlayPda)).to.be.null;
+
+        const listenerBalanceAfter = await provider.connection.getBalance(secondUserKeypair.publicKey);
+        expect(listenerBalanceAfter).to.be.greaterThan(listenerBalanceBefore);
+
+        const creatorStats = await program.account.userStats.fetch(creatorStatsPda);
+        expect(creatorStats.totalLikesReceived.toNumber()).to.equal(likesBefore - 1);
+      });
+    });
+
+    describe("Listening Insights", () => {
+      const title = "Deep Cut";
+      const artist = "Insight Artist";
+      const [testSongPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("track"), Buffer.from("Test Song"), Buffer.from("Test Artist")],
+        program.programId
+      );
+      const [deepCutPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("track"), Buffer.from(title), Buffer.from(artist)],
+        program.programId
+      );
+      const trackPlayPda = (track: PublicKey, user: Keypair) =>
+        PublicKey.findProgramAddressSync(
+          [Buffer.from("track_play"), track.toBuffer(), user.publicKey.toBuffer()],
+          program.programId
+        )[0];
+      const userStatsPda = (user: Keypair) =>
+        PublicKey.findProgramAddressSync(
+          [Buffer.from("user_stats"), user.publicKey.toBuffer()],
+          program.programId
+        )[0];
+      const [userInsightsPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_insights"), userKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const playDeepCut = (listener: Keypair, durationPlayed: number) =>
+        program.methods
+          .playTrack(new anchor.BN(durationPlayed))
+          .accounts({
+            track: deepCutPda,
+            trackPlay: trackPlayPda(deepCutPda, listener),
+            userStats: userStatsPda(listener),
+            creatorStats: userStatsPda(userKeypair),
+            user: listener.publicKey,
+            creatorEscrow: null,
+            config: null,
+            feeRecipient: null,
+            subscription: null,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([listener])
+          .rpc();
+
+      const generateInsights = (history: PublicKey[]) =>
+        program.methods
+          .generateUserInsights()
+          .accounts({
+            userInsights: userInsightsPda,
+            userStats: userStatsPda(userKeypair),
+            user: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .remainingAccounts(history.map((pubkey) => ({ pubkey, isWritable: false, isSigner: false })))
+          .signers([userKeypair])
+          .rpc();
+
+      before(async () => {
+        await program.methods
+          .createTrack(title, artist, "", "Jazz", new anchor.BN(300), "https://example.com/deep.mp3", "", new anchor.BN(0), false)
+          .accounts({
+            track: deepCutPda,
+            artistProfile: null,
+            artistNameClaim: artistNameClaimPda(artist),
+            creatorEscrow: null,
+            albumAccount: null,
+            albumTrack: null,
+            authority: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+        await playDeepCut(userKeypair, 200);
+        await playDeepCut(secondUserKeypair, 200);
+      });
+
+      it("Should derive insights from the listening history passed in", async () => {
+        await generateInsights([
+          trackPlayPda(testSongPda, userKeypair),
+          testSongPda,
+          trackPlayPda(deepCutPda, userKeypair),
+          deepCutPda,
+        ]);
+
+        const userInsights = await program.account.userInsights.fetch(userInsightsPda);
+        // 120s of "Test Song" (Pop) and 200s of "Deep Cut" (Jazz), one play each
+        expect(userInsights.totalListeningTime.toNumber()).to.equal(320);
+        expect(userInsights.favoriteGenre).to.equal("Jazz");
+        expect(userInsights.mostPlayedTrack.toString()).to.equal(deepCutPda.toString());
+        expect(userInsights.discoveryScore).to.be.closeTo(1.0, 0.0001);
+
+        const [userProfilePda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("user_profile"), userKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+        const userProfile = await program.account.userProfile.fetch(userProfilePda);
+        const userStats = await program.account.userStats.fetch(userStatsPda(userKeypair));
+        const interactions =
+          userProfile.followersCount.toNumber() +
+          userProfile.followingCount.toNumber() +
+          userStats.totalLikesReceived.toNumber() +
+          userStats.playlistsCreated.toNumber() +
+          userStats.tracksCreated.toNumber();
+        expect(interactions).to.be.greaterThan(0);
+        expect(userInsights.socialEngagement).to.be.closeTo(interactions / (interactions + 20), 0.0001);
+      });
+
+      it("Should reject another user's play records", async () => {
+        try {
+          await generateInsights([trackPlayPda(deepCutPda, secondUserKeypair), deepCutPda]);
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("InvalidAccount");
+        }
+      });
+
+      it("Should reject a track passed in twice", async () => {
+        try {
+          await generateInsights([
+            trackPlayPda(deepCutPda, userKeypair),
+            deepCutPda,
+            trackPlayPda(deepCutPda, userKeypair),
+            deepCutPda,
+          ]);
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("InvalidAccount");
+        }
+      });
+    });
+
+    describe("Payments", () => {
+      const feeRecipientKeypair = Keypair.generate();
+      const title = "Paid Cut";
+      const artist = "Paid Artist";
+      const price = 1_000_000;
+
+      const [trackPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("track"), Buffer.from(title), Buffer.from(artist)],
+        program.programId
+      );
+      const [creatorEscrowPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("creator_escrow"), userKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const createPaidTrack = (creatorEscrow: PublicKey | null) =>
+        program.methods
+          .createTrack(title, artist, "", "", new anchor.BN(120), "https://example.com/paid.mp3", "", new anchor.BN(price), false)
+          .accounts({
+            track: trackPda,
+            artistProfile: null,
+            artistNameClaim: artistNameClaimPda(artist),
+            creatorEscrow,
+            albumAccount: null,
+            albumTrack: null,
+            authority: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+      before(async () => {
+        const airdropTx = await provider.connection.requestAirdrop(
+          feeRecipientKeypair.publicKey,
+          anchor.web3.LAMPORTS_PER_SOL
+        );
+        await provider.connection.confirmTransaction(airdropTx);
+      });
+
+      it("Should require a creator escrow before pricing a track", async () => {
+        try {
+          await createPaidTrack(null);
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("Create the creator escrow before setting a track price");
+        }
+
+        await program.methods
+          .initCreatorEscrow()
+          .accounts({
+            creatorEscrow: creatorEscrowPda,
+            creator: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+        await createPaidTrack(creatorEscrowPda);
+
+        const track = await program.account.track.fetch(trackPda);
+        expect(track.priceLamports.toNumber()).to.equal(price);
+      });
+
+      it("Should split a paid play between the platform and the creator", async () => {
+        await program.methods
+          .setPlatformFee(1000, feeRecipientKeypair.publicKey) // 10%
+          .accounts({
+            config: configPda,
+            admin: provider.wallet.publicKey,
+          })
+          .rpc();
+
+        const [trackPlayPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track_play"), trackPda.toBuffer(), secondUserKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+        const [listenerStatsPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("user_stats"), secondUserKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+        const [creatorStatsPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("user_stats"), userKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+
+        const feeBalanceBefore = await provider.connection.getBalance(feeRecipientKeypair.publicKey);
+        const escrowBalanceBefore = await provider.connection.getBalance(creatorEscrowPda);
+
+        await program.methods
+          .playTrack(new anchor.BN(120))
+          .accounts({
+            track: trackPda,
+            trackPlay: trackPlayPda,
+            userStats: listenerStatsPda,
+            creatorStats: creatorStatsPda,
+            user: secondUserKeypair.publicKey,
+            creatorEscrow: creatorEscrowPda,
+            config: configPda,
+            feeRecipient: feeRecipientKeypair.publicKey,
+            subscription: null,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([secondUserKeypair])
+          .rpc();
+
+        const feeBalanceAfter = await provider.connection.getBalance(feeRecipientKeypair.publicKey);
+        const escrowBalanceAfter = await provider.connection.getBalance(creatorEscrowPda);
+        expect(feeBalanceAfter - feeBalanceBefore).to.equal(price / 10);
+        expect(escrowBalanceAfter - escrowBalanceBefore).to.equal(price - price / 10);
+
+        const escrow = await program.account.creatorEscrow.fetch(creatorEscrowPda);
+        expect(escrow.totalEarned.toNumber()).to.equal(price - price / 10);
+      });
+
+      it("Should let the creator withdraw their earnings", async () => {
+        const balanceBefore = await provider.connection.getBalance(userKeypair.publicKey);
+
+        await program.methods
+          .withdrawEarnings()
+          .accounts({
+            creatorEscrow: creatorEscrowPda,
+            creator: userKeypair.publicKey,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+        const balanceAfter = await provider.connection.getBalance(userKeypair.publicKey);
+        expect(balanceAfter).to.be.greaterThan(balanceBefore);
+
+        const escrow = await program.account.creatorEscrow.fetch(creatorEscrowPda);
+        expect(escrow.totalWithdrawn.toNumber()).to.equal(price - price / 10);
+
+        try {
+          await program.methods
+            .withdrawEarnings()
+            .accounts({
+              creatorEscrow: creatorEscrowPda,
+              creator: userKeypair.publicKey,
+            })
+            .signers([userKeypair])
+            .rpc();
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("No earnings to withdraw");
+        }
+      });
+    });
+
+    describe("Subscriptions", () => {
+      const title = "Premium Cut";
+      const artist = "Paid Artist";
+      const playlistName = "Premium Playlist";
+
+      const [trackPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("track"), Buffer.from(title), Buffer.from(artist)],
+        program.programId
+      );
+      const [playlistPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("playlist"), secondUserKeypair.publicKey.toBuffer(), Buffer.from(playlistName)],
+        program.programId
+      );
+      const [playlistTrackPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("playlist_track"), playlistPda.toBuffer(), trackPda.toBuffer()],
+        program.programId
+      );
+      const [trackPlayPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("track_play"), trackPda.toBuffer(), secondUserKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const [subscriptionPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("subscription"), secondUserKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const [treasuryPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("treasury")],
+        program.programId
+      );
+      const subscriptionPrice = 1_000_000;
+
+      const playPremium = (subscription: PublicKey | null) =>
+        program.methods
+          .playTrack(new anchor.BN(120))
+          .accounts({
+            track: trackPda,
+            trackPlay: trackPlayPda,
+            userStats: PublicKey.findProgramAddressSync(
+              [Buffer.from("user_stats"), secondUserKeypair.publicKey.toBuffer()],
+              program.programId
+            )[0],
+            creatorStats: PublicKey.findProgramAddressSync(
+              [Buffer.from("user_stats"), userKeypair.publicKey.toBuffer()],
+              program.programId
+            )[0],
+            user: secondUserKeypair.publicKey,
+            creatorEscrow: null,
+            config: null,
+            feeRecipient: null,
+            subscription,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([secondUserKeypair])
+          .rpc();
+
+      before(async () => {
+        await program.methods
+          .createTrack(title, artist, "", "", new anchor.BN(120), "https://example.com/premium.mp3", "", new anchor.BN(0), true)
+          .accounts({
+            track: trackPda,
+            artistProfile: null,
+            artistNameClaim: artistNameClaimPda(artist),
+            creatorEscrow: null,
+            albumAccount: null,
+            albumTrack: null,
+            authority: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([userKeypair])
+          .rpc();
+      });
+
+      it("Should reject premium plays without a subscription", async () => {
+        try {
+          await playPremium(null);
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("An active subscription is required for premium tracks");
+        }
+      });
+
+      it("Should reject adding premium tracks to a playlist without a subscription", async () => {
+        await program.methods
+          .createPlaylist(playlistName, "Premium only", false, false)
+          .accounts({
+            playlist: playlistPda,
+            authority: secondUserKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([secondUserKeypair])
+          .rpc();
+
+        try {
+          await program.methods
+            .addTrackToPlaylist()
+            .accounts({
+              playlist: playlistPda,
+              playlistTrack: playlistTrackPda,
+              track: trackPda,
+              playlistCollaborator: null,
+              subscription: null,
+              authority: secondUserKeypair.publicKey,
+              systemProgram: anchor.web3.SystemProgram.programId,
+            })
+            .signers([secondUserKeypair])
+            .rpc();
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("An active subscription is required for premium tracks");
+        }
+
+        // Leave the second profile without playlists so it can be closed later
+        await program.methods
+          .deletePlaylist()
+          .accounts({
+            playlist: playlistPda,
+            authority: secondUserKeypair.publicKey,
+          })
+          .signers([secondUserKeypair])
+          .rpc();
+      });
+
+      it("Should allow premium plays with an active subscription", async () => {
+        await program.methods
+          .setSubscriptionPrice(new anchor.BN(subscriptionPrice))
+          .accounts({
+            config: configPda,
+            admin: provider.wallet.publicKey,
+          })
+          .rpc();
+
+        await program.methods
+          .subscribe(1)
+          .accounts({
+            subscription: subscriptionPda,
+            treasury: treasuryPda,
+            config: configPda,
+            user: secondUserKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([secondUserKeypair])
+          .rpc();
+
+        await playPremium(subscriptionPda);
+
+        const track = await program.account.track.fetch(trackPda);
+        expect(track.playsCount.toNumber()).to.equal(1);
+      });
+
+      it("Should only let the config admin withdraw the treasury", async () => {
+        try {
+          await program.methods
+            .withdrawTreasury(new anchor.BN(subscriptionPrice))
+            .accounts({
+              treasury: treasuryPda,
+              config: configPda,
+              admin: userKeypair.publicKey,
+            })
+            .signers([userKeypair])
+            .rpc();
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("Unauthorized action");
+        }
+
+        const treasuryBefore = await provider.connection.getBalance(treasuryPda);
+
+        await program.methods
+          .withdrawTreasury(new anchor.BN(subscriptionPrice))
+          .accounts({
+            treasury: treasuryPda,
+            config: configPda,
+            admin: provider.wallet.publicKey,
+          })
+          .rpc();
+
+        const treasuryAfter = await provider.connection.getBalance(treasuryPda);
+        expect(treasuryBefore - treasuryAfter).to.equal(subscriptionPrice);
+
+        // Only the rent is left
+        try {
+          await program.methods
+            .withdrawTreasury(new anchor.BN(1))
+            .accounts({
+              treasury: treasuryPda,
+              config: configPda,
+              admin: provider.wallet.publicKey,
+            })
+            .rpc();
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("Treasury does not hold enough lamports above its rent");
+        }
+      });
+    });
+
+    describe("Artist Profiles", () => {
+      it("Should create an unverified artist profile", async () => {
+        const name = "Test Artist";
+        const [artistProfilePda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("artist_profile"), userKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+        const [userProfilePda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("user_profile"), userKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+
+        await program.methods
+          .createArtistProfile(name)
+          .accounts({
+            artistProfile: artistProfilePda,
+            userProfile: userProfilePda,
+            authority: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+        const artistProfile = await program.account.artistProfile.fetch(artistProfilePda);
+        expect(artistProfile.name).to.equal(name);
+        expect(artistProfile.userProfile.toString()).to.equal(userProfilePda.toString());
+        expect(artistProfile.isVerified).to.be.false;
+      });
+
+      it("Should reserve a verified artist's name for their profile", async () => {
+        const name = "Test Artist";
+        const [artistProfilePda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("artist_profile"), userKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+        const claimPda = artistNameClaimPda(name);
+
+        await program.methods
+          .verifyArtist(true)
+          .accounts({
+            artistProfile: artistProfilePda,
+            artistNameClaim: claimPda,
+            config: configPda,
+            admin: provider.wallet.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .rpc();
+
+        const claim = await program.account.artistNameClaim.fetch(claimPda);
+        expect(claim.artistProfile.toString()).to.equal(artistProfilePda.toString());
+
+        // Another wallet cannot publish under the verified name, whatever its case
+        const impostorArtist = "test artist";
+        const [impostorTrackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track"), Buffer.from("Fake Hit"), Buffer.from(impostorArtist)],
+          program.programId
+        );
+        try {
+          await program.methods
+            .createTrack("Fake Hit", impostorArtist, "", "", new anchor.BN(120), "https://example.com/fake.mp3", "", new anchor.BN(0), false)
+            .accounts({
+              track: impostorTrackPda,
+              artistProfile: null,
+              artistNameClaim: artistNameClaimPda(impostorArtist),
+              creatorEscrow: null,
+              albumAccount: null,
+              albumTrack: null,
+              authority: secondUserKeypair.publicKey,
+              systemProgram: anchor.web3.SystemProgram.programId,
+            })
+            .signers([secondUserKeypair])
+            .rpc();
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("Artist name belongs to a verified artist");
+        }
+
+        // The artist can, by linking their profile
+        const [trackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track"), Buffer.from("Verified Hit"), Buffer.from(name)],
+          program.programId
+        );
+        await program.methods
+          .createTrack("Verified Hit", name, "", "", new anchor.BN(120), "https://example.com/verified.mp3", "", new anchor.BN(0), false)
+          .accounts({
+            track: trackPda,
+            artistProfile: artistProfilePda,
+            artistNameClaim: claimPda,
+            creatorEscrow: null,
+            albumAccount: null,
+            albumTrack: null,
+            authority: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+        const track = await program.account.track.fetch(trackPda);
+        expect(track.artistProfile.toString()).to.equal(artistProfilePda.toString());
+      });
+    });
+
+    describe("Albums", () => {
+      it("Should create an album and add a track to it", async () => {
+        const albumTitle = "Enhanced Album";
+        const [albumPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("album"), userKeypair.publicKey.toBuffer(), Buffer.from(albumTitle)],
+          program.programId
+        );
+        const [trackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track"), Buffer.from("Enhanced Track"), Buffer.from("Enhanced Artist")],
+          program.programId
+        );
+        const [albumTrackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("album_track"), albumPda.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
+          program.programId
+        );
+
+        await program.methods
+          .createAlbum(albumTitle, "https://example.com/album.jpg", null)
+          .accounts({
+            album: albumPda,
+            authority: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+        await program.methods
+          .addTrackToAlbum()
+          .accounts({
+            album: albumPda,
+            albumTrack: albumTrackPda,
+            track: trackPda,
+            authority: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+        const album = await program.account.album.fetch(albumPda);
+        expect(album.trackCount.toNumber()).to.equal(1);
+        const albumTrack = await program.account.albumTrack.fetch(albumTrackPda);
+        expect(albumTrack.track.toString()).to.equal(trackPda.toString());
+        expect(albumTrack.position.toNumber()).to.equal(0);
+        const track = await program.account.track.fetch(trackPda);
+        expect(track.albumKey.toString()).to.equal(albumPda.toString());
+      });
+
+      it("Should refuse to rename the album of a linked track", async () => {
+        try {
+          await updateEnhancedTrack(userKeypair, { album: "Another Album" });
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("Track already belongs to an album");
+        }
+      });
+
+      it("Should refuse to delete a track that belongs to an album", async () => {
+        const [trackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track"), Buffer.from("Enhanced Track"), Buffer.from("Enhanced Artist")],
+          program.programId
+        );
+
+        try {
+          await program.methods
+            .deleteTrack()
+            .accounts({
+              track: trackPda,
+              authority: userKeypair.publicKey,
+            })
+            .signers([userKeypair])
+            .rpc();
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("Track belongs to an album");
+        }
+      });
+    });
+
+    describe("Collaborative Playlists", () => {
+      it("Should create collaborative playlist", async () => {
+        const playlistName = "Collaborative Playlist";
+        const description = "A playlist for collaboration";
+        const isPublic = true;
+        const isCollaborative = true;
+
+        const [playlistPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("playlist"), userKeypair.publicKey.toBuffer(), Buffer.from(playlistName)],
+          program.programId
+        );
+
+        const [userStatsPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("user_stats"), userKeypair.publicKey.toBuffer()],
+          program.programId
+        );
+
+        await program.methods
+          .createPlaylist(playlistName, description, isPublic, isCollaborative)
+          .accounts({
+            playlist: playlistPda,
+            userStats: userStatsPda,
+            authority: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+        // Verify collaborative playlist was created
+        const playlist = await program.account.playlist.fetch(playlistPda);
+        expect(playlist.name).to.equal(playlistName);
+        expect(playlist.isCollaborative).to.equal(true);
+        expect(playlist.isPublic).to.equal(true);
+      });
+
+      it("Should add collaborator to playlist", async () => {
+        const playlistName = "Collaborative Playlist";
+        const permissions = 7; // All permissions
+
+        const [playlistPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("playlist"), userKeypair.publicKey.toBuffer(), Buffer.from(playlistName)],
+          program.programId
+        );
+
+        const [playlistCollaboratorPda] = PublicKey.findProgramAddressSync(
           [
-            Buffer.from("track_play"),
-            trackPda.toBuffer(),
-            userKeypair.publicKey.toBuffer(),
-            Buffer.from(timestamp.toString())
+            Buffer.from("playlist_collaborator"),
+            playlistPda.toBuffer(),
+            secondUserKeypair.publicKey.toBuffer()
           ],
           program.programId
         );
 
         await program.methods
-          .playTrack(new anchor.BN(durationPlayed))
+          .addCollaborator(permissions)
           .accounts({
-            track: trackPda,
-            trackPlay: trackPlayPda,
-            userStats: userStatsPda,
-            creatorStats: creatorStatsPda,
-            user: userKeypair.publicKey,
+            playlist: playlistPda,
+            playlistCollaborator: playlistCollaboratorPda,
+            collaborator: secondUserKeypair.publicKey,
+            authority: userKeypair.publicKey,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
           .signers([userKeypair])
           .rpc();
 
-        // Verify track play was recorded
-        const trackPlay = await program.account.trackPlay.fetch(trackPlayPda);
-        expect(trackPlay.track.toString()).to.equal(trackPda.toString());
-        expect(trackPlay.user.toString()).to.equal(userKeypair.publicKey.toString());
-        expect(trackPlay.durationPlayed.toNumber()).to.equal(durationPlayed);
-
-        // Verify track play count increased
-        const track = await program.account.track.fetch(trackPda);
-        expect(track.playsCount.toNumber()).to.equal(1);
+        // Verify collaborator was added
+        const collaborator = await program.account.playlistCollaborator.fetch(playlistCollaboratorPda);
+        expect(collaborator.playlist.toString()).to.equal(playlistPda.toString());
+        expect(collaborator.user.toString()).to.equal(secondUserKeypair.publicKey.toString());
+        expect(collaborator.permissions).to.equal(permissions);
       });
 
-      it("Should create enhanced track with genre", async () => {
-        const title = "Enhanced Track";
-        const artist = "Enhanced Artist";
-        const album = "Enhanced Album";
-        const genre = "Electronic";
-        const duration = 240;
-        const audioUrl = "https://example.com/enhanced.mp3";
-        const coverImage = "https://example.com/enhanced-cover.jpg";
+      it("Should restrict collaborator to granted permissions", async () => {
+        const playlistName = "Collaborative Playlist";
+
+        const [playlistPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("playlist"), userKeypair.publicKey.toBuffer(), Buffer.from(playlistName)],
+          program.programId
+        );
+
+        const [playlistCollaboratorPda] = PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("playlist_collaborator"),
+            playlistPda.toBuffer(),
+            secondUserKeypair.publicKey.toBuffer()
+          ],
+          program.programId
+        );
 
         const [trackPda] = PublicKey.findProgramAddressSync(
-          [Buffer.from("track"), Buffer.from(title), Buffer.from(artist)],
+          [Buffer.from("track"), Buffer.from("Test Song"), Buffer.from("Test Artist")],
           program.programId
         );
 
-        const [userStatsPda] = PublicKey.findProgramAddressSync(
-          [Buffer.from("user_stats"), userKeypair.publicKey.toBuffer()],
+        const [playlistTrackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("playlist_track"), playlistPda.toBuffer(), trackPda.toBuffer()],
           program.programId
         );
 
+        // Narrow the collaborator down to adding tracks only
         await program.methods
-          .createTrack(title, artist, album, genre, new anchor.BN(duration), audioUrl, coverImage)
+          .updateCollaboratorPermissions(1)
           .accounts({
-            track: trackPda,
-            userStats: userStatsPda,
+            playlist: playlistPda,
+            playlistCollaborator: playlistCollaboratorPda,
             authority: userKeypair.publicKey,
-            systemProgram: anchor.web3.SystemProgram.programId,
           })
           .signers([userKeypair])
           .rpc();
 
-        // Verify enhanced track was created
-        const track = await program.account.track.fetch(trackPda);
-        expect(track.title).to.equal(title);
-        expect(track.artist).to.equal(artist);
-        expect(track.genre).to.equal(genre);
-        expect(track.createdBy.toString()).to.equal(userKeypair.publicKey.toString());
+        const collaborator = await program.account.playlistCollaborator.fetch(playlistCollaboratorPda);
+        expect(collaborator.permissions).to.equal(1);
+
+        // Adding tracks is granted
+        await program.methods
+          .addTrackToPlaylist()
+          .accounts({
+            playlist: playlistPda,
+            playlistTrack: playlistTrackPda,
+            track: trackPda,
+            playlistCollaborator: playlistCollaboratorPda,
+            subscription: null,
+            authority: secondUserKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([secondUserKeypair])
+          .rpc();
+
+        const playlistTrack = await program.account.playlistTrack.fetch(playlistTrackPda);
+        expect(playlistTrack.addedBy.toString()).to.equal(secondUserKeypair.publicKey.toString());
+
+        // Editing details is not
+        try {
+          await program.methods
+            .updatePlaylist("Rewritten by a collaborator", null, null)
+            .accounts({
+              playlist: playlistPda,
+              playlistCollaborator: playlistCollaboratorPda,
+              authority: secondUserKeypair.publicKey,
+            })
+            .signers([secondUserKeypair])
+            .rpc();
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("No permission to edit this playlist");
+        }
+
+        // Neither is removing tracks
+        try {
+          await program.methods
+            .removeTrackFromPlaylist()
+            .accounts({
+              playlist: playlistPda,
+              playlistTrack: playlistTrackPda,
+              track: trackPda,
+              addedBy: secondUserKeypair.publicKey,
+              playlistCollaborator: playlistCollaboratorPda,
+              authority: secondUserKeypair.publicKey,
+            })
+            .signers([secondUserKeypair])
+            .rpc();
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("No permission to remove track from this playlist");
+        }
+
+        const playlist = await program.account.playlist.fetch(playlistPda);
+        expect(playlist.tracksCount.toNumber()).to.equal(1);
       });
-    });
 
-    describe("Collaborative Playlists", () => {
-      it("Should create collaborative playlist", async () => {
+      it("Should remove collaborator and revoke access", async () => {
         const playlistName = "Collaborative Playlist";
-        const description = "A playlist for collaboration";
-        const isPublic = true;
-        const isCollaborative = true;
 
         const [playlistPda] = PublicKey.findProgramAddressSync(
           [Buffer.from("playlist"), userKeypair.publicKey.toBuffer(), Buffer.from(playlistName)],
           program.programId
         );
 
-        const [userStatsPda] = PublicKey.findProgramAddressSync(
-          [Buffer.from("user_stats"), userKeypair.publicKey.toBuffer()],
+        const [playlistCollaboratorPda] = PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("playlist_collaborator"),
+            playlistPda.toBuffer(),
+            secondUserKeypair.publicKey.toBuffer()
+          ],
           program.programId
         );
 
         await program.methods
-          .createPlaylist(playlistName, description, isPublic, isCollaborative)
+          .removeCollaborator()
           .accounts({
             playlist: playlistPda,
-            userStats: userStatsPda,
+            playlistCollaborator: playlistCollaboratorPda,
             authority: userKeypair.publicKey,
-            systemProgram: anchor.web3.SystemProgram.programId,
           })
           .signers([userKeypair])
           .rpc();
 
-        // Verify collaborative playlist was created
-        const playlist = await program.account.playlist.fetch(playlistPda);
-        expect(playlist.name).to.equal(playlistName);
-        expect(playlist.isCollaborative).to.equal(true);
-        expect(playlist.isPublic).to.equal(true);
+        expect(await provider.connection.getAccountInfo(playlistCollaboratorPda)).to.be.null;
+
+        const [trackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("track"), Buffer.from("First Cut"), Buffer.from("Ordering Artist")],
+          program.programId
+        );
+
+        const [playlistTrackPda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("playlist_track"), playlistPda.toBuffer(), trackPda.toBuffer()],
+          program.programId
+        );
+
+        try {
+          await program.methods
+            .addTrackToPlaylist()
+            .accounts({
+              playlist: playlistPda,
+              playlistTrack: playlistTrackPda,
+              track: trackPda,
+              playlistCollaborator: null,
+              subscription: null,
+              authority: secondUserKeypair.publicKey,
+              systemProgram: anchor.web3.SystemProgram.programId,
+            })
+            .signers([secondUserKeypair])
+            .rpc();
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("No permission to add track to this playlist");
+        }
       });
 
-      it("Should add collaborator to playlist", async () => {
-        const playlistName = "Collaborative Playlist";
-        const permissions = 7; // All permissions
+      it("Should delete a playlist together with its likes and collaborators", async () => {
+        const playlistName = "Disposable Playlist";
 
         const [playlistPda] = PublicKey.findProgramAddressSync(
           [Buffer.from("playlist"), userKeypair.publicKey.toBuffer(), Buffer.from(playlistName)],
@@ -730,8 +2867,23 @@ describe("spotify_dapp", () => {
           program.programId
         );
 
+        const [playlistLikePda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("playlist_like"), userKeypair.publicKey.toBuffer(), playlistPda.toBuffer()],
+          program.programId
+        );
+
         await program.methods
-          .addCollaborator(permissions)
+          .createPlaylist(playlistName, "Created to be deleted", false, true)
+          .accounts({
+            playlist: playlistPda,
+            authority: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+        await program.methods
+          .addCollaborator(1)
           .accounts({
             playlist: playlistPda,
             playlistCollaborator: playlistCollaboratorPda,
@@ -742,11 +2894,53 @@ describe("spotify_dapp", () => {
           .signers([userKeypair])
           .rpc();
 
-        // Verify collaborator was added
-        const collaborator = await program.account.playlistCollaborator.fetch(playlistCollaboratorPda);
-        expect(collaborator.playlist.toString()).to.equal(playlistPda.toString());
-        expect(collaborator.user.toString()).to.equal(secondUserKeypair.publicKey.toString());
-        expect(collaborator.permissions).to.equal(permissions);
+        await program.methods
+          .likePlaylist()
+          .accounts({
+            playlist: playlistPda,
+            playlistLike: playlistLikePda,
+            user: userKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([userKeypair])
+          .rpc();
+
+        const playlist = await program.account.playlist.fetch(playlistPda);
+        expect(playlist.collaboratorsCount.toNumber()).to.equal(1);
+        expect(playlist.likesCount.toNumber()).to.equal(1);
+
+        // The like and collaborator entries must be closed along with the playlist
+        try {
+          await program.methods
+            .deletePlaylist()
+            .accounts({
+              playlist: playlistPda,
+              authority: userKeypair.publicKey,
+            })
+            .signers([userKeypair])
+            .rpc();
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorMessage).to.include("Playlist still has tracks, likes or collaborators");
+        }
+
+        await program.methods
+          .deletePlaylist()
+          .accounts({
+            playlist: playlistPda,
+            authority: userKeypair.publicKey,
+          })
+          .remainingAccounts([
+            { pubkey: playlistLikePda, isWritable: true, isSigner: false },
+            { pubkey: userKeypair.publicKey, isWritable: true, isSigner: false },
+            { pubkey: playlistCollaboratorPda, isWritable: true, isSigner: false },
+          ])
+          .signers([userKeypair])
+          .rpc();
+
+        expect(await provider.connection.getAccountInfo(playlistPda)).to.be.null;
+        expect(await provider.connection.getAccountInfo(playlistLikePda)).to.be.null;
+        expect(await provider.connection.getAccountInfo(playlistCollaboratorPda)).to.be.null;
       });
     });
 
@@ -775,14 +2969,8 @@ describe("spotify_dapp", () => {
           program.programId
         );
 
-        // Create activity feed PDA with timestamp
-        const timestamp = Date.now();
         const [activityFeedPda] = PublicKey.findProgramAddressSync(
-          [
-            Buffer.from("activity_feed"),
-            secondUserKeypair.publicKey.toBuffer(),
-            Buffer.from(timestamp.toString())
-          ],
+          [Buffer.from("activity_feed"), secondUserKeypair.publicKey.toBuffer()],
           program.programId
         );
 
@@ -803,9 +2991,253 @@ describe("spotify_dapp", () => {
         // Verify activity feed was created
         const activityFeed = await program.account.activityFeed.fetch(activityFeedPda);
         expect(activityFeed.user.toString()).to.equal(secondUserKeypair.publicKey.toString());
-        expect(activityFeed.activityType).to.equal(1); // ACTIVITY_TRACK_LIKED
-        expect(activityFeed.target.toString()).to.equal(trackPda.toString());
+        const latest = activityFeed.entries[(activityFeed.head.toNumber() - 1) % 32];
+        expect(latest.activityType).to.equal(1); // ACTIVITY_TRACK_LIKED
+        expect(latest.target.toString()).to.equal(trackPda.toString());
+      });
+    });
+    describe("Activity Feed Migration", () => {
+      // Owner of the legacy feed preloaded by Anchor.toml from tests/fixtures
+      const legacyOwnerKeypair = Keypair.fromSecretKey(
+        Uint8Array.from(JSON.parse(readFileSync("tests/fixtures/legacy-feed-owner.json", "utf8")))
+      );
+      const [activityFeedPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("activity_feed"), legacyOwnerKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const [trackPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("track"), Buffer.from("Test Song"), Buffer.from("Test Artist")],
+        program.programId
+      );
+      const [trackLikePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("track_like"), legacyOwnerKeypair.publicKey.toBuffer(), trackPda.toBuffer()],
+        program.programId
+      );
+      const [userStatsPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_stats"), legacyOwnerKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const [creatorStatsPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_stats"), userKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const migrate = () =>
+        program.methods
+          .migrateActivityFeed()
+          .accounts({
+            activityFeed: activityFeedPda,
+            user: legacyOwnerKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([legacyOwnerKeypair])
+          .rpc();
+
+      before(async function () {
+        // The fixture is only loaded by the local test validator
+        if (!(await provider.connection.getAccountInfo(activityFeedPda))) {
+          this.skip();
+        }
+
+        const airdropTx = await provider.connection.requestAirdrop(
+          legacyOwnerKeypair.publicKey,
+          anchor.web3.LAMPORTS_PER_SOL
+        );
+        await provider.connection.confirmTransaction(airdropTx);
+
+        await program.methods
+          .createUserProfile("legacyowner", "Legacy Owner", "", "")
+          .accounts({
+            userProfile: PublicKey.findProgramAddressSync(
+              [Buffer.from("user_profile"), legacyOwnerKeypair.publicKey.toBuffer()],
+              program.programId
+            )[0],
+            usernameClaim: usernameClaimPda("legacyowner"),
+            authority: legacyOwnerKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([legacyOwnerKeypair])
+          .rpc();
+      });
+
+      it("Should migrate a legacy feed and keep its entry", async () => {
+        const legacyInfo = await provider.connection.getAccountInfo(activityFeedPda);
+        expect(legacyInfo.data.length).to.equal(157);
+
+        await migrate();
+
+        const migratedInfo = await provider.connection.getAccountInfo(activityFeedPda);
+        expect(migratedInfo.data.length).to.equal(8 + 3532);
+        expect(migratedInfo.lamports).to.be.greaterThan(legacyInfo.lamports);
+
+        const activityFeed = await program.account.activityFeed.fetch(activityFeedPda);
+        expect(activityFeed.user.toString()).to.equal(legacyOwnerKeypair.publicKey.toString());
+        expect(activityFeed.head.toNumber()).to.equal(1);
+        expect(activityFeed.entries.length).to.equal(1);
+        expect(activityFeed.entries[0].activityType).to.equal(3); // ACTIVITY_USER_FOLLOWED
+        expect(activityFeed.entries[0].metadata).to.equal("Legacy activity");
+        expect(activityFeed.entries[0].createdAt.toNumber()).to.equal(1_700_000_000);
+      });
+
+      it("Should reject migrating a feed twice", async () => {
+        try {
+          await migrate();
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.error.errorCode.code).to.equal("ActivityFeedAlreadyMigrated");
+        }
+      });
+
+      it("Should wrap the feed once it holds more than CAPACITY entries", async () => {
+        const accounts = {
+          track: trackPda,
+          trackLike: trackLikePda,
+          userStats: userStatsPda,
+          creatorStats: creatorStatsPda,
+          activityFeed: activityFeedPda,
+          user: legacyOwnerKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        };
+
+        // 17 like/unlike rounds add 34 entries on top of the migrated one
+        for (let round = 0; round < 17; round++) {
+          await program.methods.likeTrack().accounts(accounts).signers([legacyOwnerKeypair]).rpc();
+          await program.methods.unlikeTrack().accounts(accounts).signers([legacyOwnerKeypair]).rpc();
+        }
+
+        const activityFeed = await program.account.activityFeed.fetch(activityFeedPda);
+        expect(activityFeed.head.toNumber()).to.equal(35);
+        expect(activityFeed.entries.length).to.equal(32);
+
+        const newest = activityFeed.entries[(activityFeed.head.toNumber() - 1) % 32];
+        expect(newest.activityType).to.equal(7); // ACTIVITY_TRACK_UNLIKED
+        expect(newest.target.toString()).to.equal(trackPda.toString());
+
+        // The migrated entry in slot 0 has been overwritten by a later activity
+        expect(activityFeed.entries[0].metadata).to.not.equal("Legacy activity");
+        expect(activityFeed.entries.some((entry) => entry.activityType === 3)).to.equal(false);
       });
     });
   });
+
+  describe("Profile Lifecycle Tests", () => {
+    const thirdUserKeypair = Keypair.generate();
+
+    const profilePda = (authority: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("user_profile"), authority.toBuffer()],
+        program.programId
+      )[0];
+
+    const followPda = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("user_follow"),
+        thirdUserKeypair.publicKey.toBuffer(),
+        profilePda(secondUserKeypair.publicKey).toBuffer()
+      ],
+      program.programId
+    )[0];
+
+    const createProfile = (user: Keypair, username: string, displayName: string) =>
+      program.methods
+        .createUserProfile(username, displayName, "", "")
+        .accounts({
+          userProfile: profilePda(user.publicKey),
+          usernameClaim: usernameClaimPda(username),
+          authority: user.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+
+    const closeProfile = (user: Keypair, username: string) =>
+      program.methods
+        .closeUserProfile()
+        .accounts({
+          userProfile: profilePda(user.publicKey),
+          usernameClaim: usernameClaimPda(username),
+          activityFeed: null,
+          userInsights: null,
+          authority: user.publicKey,
+        })
+        .signers([user])
+        .rpc();
+
+    before(async () => {
+      const airdropTx = await provider.connection.requestAirdrop(
+        thirdUserKeypair.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdropTx);
+
+      await createProfile(thirdUserKeypair, "thirduser", "Third User");
+
+      await program.methods
+        .followUser()
+        .accounts({
+          followerProfile: profilePda(thirdUserKeypair.publicKey),
+          followingProfile: profilePda(secondUserKeypair.publicKey),
+          userFollow: followPda,
+          follower: thirdUserKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([thirdUserKeypair])
+        .rpc();
+    });
+
+    it("Should refuse to close a profile that still has tracks or playlists", async () => {
+      try {
+        await closeProfile(userKeypair, "testuser");
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.error.errorMessage).to.include("Delete all tracks and playlists");
+      }
+    });
+
+    it("Should refuse to unfollow a profile that was closed and re-created", async () => {
+      // Make sure the re-created profile gets a later timestamp than the follow
+      await new Promise((resolve) => setTimeout(resolve, 1500));
+
+      await closeProfile(secondUserKeypair, "seconduser");
+      expect(await provider.connection.getAccountInfo(profilePda(secondUserKeypair.publicKey))).to.be.null;
+      await createProfile(secondUserKeypair, "seconduser", "Second User");
+
+      try {
+        await program.methods
+          .unfollowUser()
+          .accounts({
+            followerProfile: profilePda(thirdUserKeypair.publicKey),
+            followingProfile: profilePda(secondUserKeypair.publicKey),
+            userFollow: followPda,
+            follower: thirdUserKeypair.publicKey,
+          })
+          .signers([thirdUserKeypair])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.error.errorMessage).to.include("Followed profile was closed or re-created");
+      }
+
+      const followingProfile = await program.account.userProfile.fetch(profilePda(secondUserKeypair.publicKey));
+      expect(followingProfile.followersCount.toNumber()).to.equal(0);
+    });
+
+    it("Should close an empty profile after removing its orphaned follow", async () => {
+      await program.methods
+        .removeOrphanedFollow()
+        .accounts({
+          followerProfile: profilePda(thirdUserKeypair.publicKey),
+          followingProfile: profilePda(secondUserKeypair.publicKey),
+          userFollow: followPda,
+          follower: thirdUserKeypair.publicKey,
+        })
+        .signers([thirdUserKeypair])
+        .rpc();
+
+      await closeProfile(thirdUserKeypair, "thirduser");
+
+      expect(await provider.connection.getAccountInfo(profilePda(thirdUserKeypair.publicKey))).to.be.null;
+      expect(await provider.connection.getAccountInfo(usernameClaimPda("thirduser"))).to.be.null;
+    });
+  });
 });